@@ -0,0 +1,16 @@
+use std::thread;
+
+use crate::board::Board;
+use crate::solver::solve;
+
+/// Solve every puzzle with `solver::solve`, spread over up to `threads` threads. Results line up with the
+/// input: `out[i]` is the solution of `puzzles[i]`, or `None` if it has none.
+pub fn solve_all(puzzles: &[Board], threads: usize) -> Vec<Option<Board>> {
+    if puzzles.is_empty() { return Vec::new(); }
+    // Contiguous chunks, one per thread, joined back in order
+    let chunk = puzzles.len().div_ceil(threads.max(1));
+    thread::scope(|s| {
+        let handles: Vec<_> = puzzles.chunks(chunk).map(|part| s.spawn(move || part.iter().map(solve).collect::<Vec<_>>())).collect();
+        handles.into_iter().flat_map(|h| h.join().expect("solver thread panicked")).collect()
+    })
+}
@@ -1,31 +1,76 @@
 use serde::{Deserialize, Serialize};
+use crate::devlog::SessionLog;
+use crate::solver::{Step, StepKind};
 use std::fmt::{self, Display, Formatter};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Cell {
     pub value: u8,        // 0 for empty
     pub fixed: bool,      // given by puzzle
 }
-a
-impl Default for Cell {
-    fn default() -> Self { Self { value: 0, fixed: false } }
+
+/// One of the 27 units (rows, columns and boxes) a digit may appear in once. Boxes are numbered 0..9
+/// left to right, top to bottom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unit { Row(usize), Col(usize), Box(usize) }
+
+/// How a cell looks when crosshatching one digit, see `Board::crosshatch`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellMark {
+    /// A puzzle given (`fixed`), with this value
+    Given(u8),
+    /// Filled in by the player, with this value; it blocks its peers like a given but may be wrong
+    Entered(u8),
+    /// Empty, but a peer in its row, column or box holds the digit
+    Blocked,
+    /// Empty and free to take the digit
+    Candidate,
+}
+
+/// A peer (row, col) holding a digit, and the unit it shares with the cell being explained.
+pub type Blocker = (usize, usize, &'static str);
+
+impl Unit {
+    /// All 27 units: rows, then columns, then boxes.
+    pub fn all() -> impl Iterator<Item = Unit> {
+        (0..9).map(Unit::Row).chain((0..9).map(Unit::Col)).chain((0..9).map(Unit::Box))
+    }
+
+    /// The (row, col) positions in the unit, in reading order.
+    pub fn positions(self) -> [(usize, usize); 9] {
+        std::array::from_fn(|i| match self { Unit::Row(r) => (r, i), Unit::Col(c) => (i, c), Unit::Box(b) => (b/3*3 + i/3, b%3*3 + i%3) })
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Numbered from 1 for display, e.g. "Row 3" or "Box 5".
+impl Display for Unit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self { Unit::Row(r) => write!(f, "Row {}", r + 1), Unit::Col(c) => write!(f, "Column {}", c + 1), Unit::Box(b) => write!(f, "Box {}", b + 1) }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Board {
     pub cells: [[Cell; 9]; 9],
+    // Optional candidate cache: bit v set if v is allowed. Only trusted while `cands_valid`;
+    // writes through `set_value` keep it in sync, direct writes to `cells` do not.
+    #[serde(skip)]
+    cands: [[u16; 9]; 9],
+    #[serde(skip)]
+    cands_valid: bool,
 }
 
+// Equality is about the grid contents; the candidate cache is a derived detail
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool { self.cells == other.cells }
+}
+impl Eq for Board {}
+
 impl Board {
-    pub fn empty() -> Self { Self { cells: [[Cell::default(); 9]; 9] } }
+    pub fn empty() -> Self { Self { cells: [[Cell::default(); 9]; 9], cands: [[0; 9]; 9], cands_valid: false } }
 
     pub fn from_rows(rows: [[u8; 9]; 9]) -> Self {
-        let mut b = Self::empty();
-        for r in 0..9 { for c in 0..9 {
-            let v = rows[r][c];
-            b.cells[r][c] = Cell { value: v, fixed: v != 0 };
-        }}
-        b
+        Self { cells: rows.map(|row| row.map(|v| Cell { value: v, fixed: v != 0 })), ..Self::empty() }
     }
 
     pub fn parse(text: &str) -> anyhow::Result<Self> {
@@ -44,88 +89,374 @@ impl Board {
         Ok(b)
     }
 
+    /// Like `parse`, but only `blank` marks an empty cell and anything other than 1-9, `blank` or line breaks
+    /// is an error rather than skipped. With a blank other than ' ', other whitespace separates cells too.
+    pub fn parse_strict(text: &str, blank: char) -> anyhow::Result<Self> {
+        if blank.is_ascii_digit() && blank != '0' { anyhow::bail!("'{}' can't mark blanks, it is a digit", blank); }
+        let mut digits = String::with_capacity(81);
+        for (i, ch) in text.chars().enumerate() {
+            match ch {
+                '1'..='9' => digits.push(ch),
+                ch if ch == blank => digits.push('.'),
+                '\n' | '\r' => {}
+                ch if ch.is_whitespace() && blank != ' ' => {}
+                ch => anyhow::bail!("unexpected '{}' at position {} (blank is '{}')", ch, i + 1, blank),
+            }
+        }
+        if digits.len() != 81 { anyhow::bail!("expected 81 cells, got {}", digits.len()); }
+        Self::parse(&digits)
+    }
+
+    /// The grid as one 81-character line, '.' for blanks; `parse` reads it back.
+    pub fn to_compact(&self) -> String {
+        self.cells.iter().flatten().map(|c| if c.value == 0 { '.' } else { char::from(b'0' + c.value) }).collect()
+    }
+
+    /// `to_compact` plus a second 81-character line marking givens with `F` and everything else with `.`,
+    /// so user entries survive a save without going through JSON.
+    pub fn to_annotated(&self) -> String {
+        let flags: String = self.cells.iter().flatten().map(|c| if c.fixed { 'F' } else { '.' }).collect();
+        format!("{}\n{}", self.to_compact(), flags)
+    }
+
+    pub fn from_annotated(text: &str) -> anyhow::Result<Self> {
+        let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+        let (Some(grid), Some(flags)) = (lines.next(), lines.next()) else { anyhow::bail!("expected a grid line and a flags line") };
+        let mut b = Self::parse(grid)?;
+        if flags.chars().count() != 81 { anyhow::bail!("expected 81 flags, got {}", flags.chars().count()); }
+        for (i, ch) in flags.chars().enumerate() {
+            let cell = &mut b.cells[i / 9][i % 9];
+            cell.fixed = match ch {
+                'F' if cell.value != 0 => true,
+                '.' => false,
+                _ => anyhow::bail!("bad flag '{}' at cell {}", ch, i + 1),
+            };
+        }
+        Ok(b)
+    }
+
+    /// Read a grid out of a puzzle-site URL or bare query string such as `?puzzle=53..7....`. Each query
+    /// value is tried in turn and the first that parses as a grid wins. Text without a `?` is read as the query
+    /// itself, so `puzzle=53..7....` or a bare grid works too.
+    pub fn from_url(s: &str) -> anyhow::Result<Self> {
+        let s = s.trim();
+        let query = s.split_once('?').map(|(_, q)| q).unwrap_or(s);
+        for pair in query.split(['&', ';', '#']) {
+            let value = pair.split_once('=').map(|(_, v)| v).unwrap_or(pair);
+            // Some sites percent-encode the dots
+            let value = value.replace("%2E", ".").replace("%2e", ".");
+            if let Ok(b) = crate::io::parse_any(&value) { return Ok(b); }
+        }
+        anyhow::bail!("no 81-cell grid found in '{}'", s)
+    }
+
+    /// Serialize to JSON, keeping the `fixed` flags that the plain .sdk format loses.
+    pub fn to_json(&self) -> String { serde_json::to_string(self).expect("board serializes") }
+
+    pub fn from_json(s: &str) -> anyhow::Result<Self> { Ok(serde_json::from_str(s)?) }
+
+    /// Replay one solver step. `Backtrack` and `Eliminate` are no-ops; the step's own `board` holds the exact post-state.
+    pub fn apply_step(&mut self, step: &Step) {
+        match step.kind {
+            StepKind::Place{ r, c, v, .. } | StepKind::Guess{ r, c, v } => self.set_value(r, c, v),
+            StepKind::Backtrack | StepKind::Eliminate{ .. } => {}
+        }
+    }
+
+    /// The board after the last step of a saved session, if it has any steps.
+    pub fn from_session_final(log: &SessionLog) -> Option<Board> { log.steps.last().map(|s| s.board.clone()) }
+
     pub fn is_valid(&self) -> bool {
         // rows, cols, boxes have no duplicates ignoring zeros
         for r in 0..9 { if !no_dupes(self.row_values(r)) { return false; } }
         for c in 0..9 { if !no_dupes(self.col_values(c)) { return false; } }
-        for br in 0..3 { for bc in 0..3 { if !no_dupes(self.box_values(br, bc)) { return false; } }}
+        for bx in 0..9 { if !no_dupes(self.box_positions(bx).map(|(r, c)| self.cells[r][c].value)) { return false; } }
         true
     }
 
     pub fn is_solved(&self) -> bool { self.cells.iter().all(|row| row.iter().all(|c| c.value != 0)) && self.is_valid() }
 
-    pub fn row_values(&self, r: usize) -> [u8; 9] { let mut a=[0;9]; for c in 0..9 { a[c]=self.cells[r][c].value; } a }
-    pub fn col_values(&self, c: usize) -> [u8; 9] { let mut a=[0;9]; for r in 0..9 { a[r]=self.cells[r][c].value; } a }
-    pub fn box_values(&self, br: usize, bc: usize) -> [u8; 9] {
-        let mut a=[0;9];
-        let mut i=0;
-        for r in br*3..br*3+3 { for c in bc*3..bc*3+3 { a[i]=self.cells[r][c].value; i+=1; }}
+    /// Numeric difficulty, see `puzzle::difficulty_score`.
+    pub fn rate(&self) -> u32 { crate::puzzle::difficulty_score(self) }
+
+    /// Number of cells holding a value, givens included.
+    pub fn filled_count(&self) -> usize { self.cells.iter().flatten().filter(|c| c.value != 0).count() }
+
+    /// 64-bit FNV-1a hash of the 81 cell values. Fixed flags and candidates are ignored, and the value is
+    /// the same on every run and platform, so it can key caches or spot duplicate puzzles.
+    pub fn content_hash(&self) -> u64 {
+        let mut h: u64 = 0xcbf29ce484222325;
+        for cell in self.cells.iter().flatten() { h = (h ^ cell.value as u64).wrapping_mul(0x100000001b3); }
+        h
+    }
+
+    /// Share of the 81 cells filled, from 0.0 to 1.0.
+    pub fn completion_ratio(&self) -> f32 { self.filled_count() as f32 / 81.0 }
+
+    /// Empty cells with exactly two candidates, in reading order, as (row, col, lower digit, higher digit).
+    pub fn bivalue_cells(&self) -> Vec<(usize, usize, u8, u8)> {
+        let mut out = Vec::new();
+        for r in 0..9 { for c in 0..9 {
+            if self.cells[r][c].value != 0 { continue; }
+            let cand = self.candidates(r, c);
+            let digits: Vec<u8> = (1..=9u8).filter(|&v| cand[v as usize]).collect();
+            if let [x, y] = digits[..] { out.push((r, c, x, y)); }
+        }}
+        out
+    }
+
+    /// The crosshatching picture for `digit`: cross out every empty cell whose row, column or box already
+    /// holds it. A box with a single `Candidate` left is a hidden single.
+    pub fn crosshatch(&self, digit: u8) -> [[CellMark; 9]; 9] {
+        std::array::from_fn(|r| std::array::from_fn(|c| {
+            let v = self.cells[r][c].value;
+            if v != 0 && self.cells[r][c].fixed { CellMark::Given(v) }
+            else if v != 0 { CellMark::Entered(v) }
+            else if self.row_values(r).contains(&digit) || self.col_values(c).contains(&digit) || self.box_values(r/3, c/3).contains(&digit) { CellMark::Blocked }
+            else { CellMark::Candidate }
+        }))
+    }
+
+    /// A copy keeping only the `fixed` cells (the puzzle's givens); every other cell is cleared.
+    pub fn givens_only(&self) -> Board {
+        let mut b = self.clone();
+        b.invalidate_candidates();
+        for cell in b.cells.iter_mut().flatten() { if !cell.fixed { cell.value = 0; } }
+        b
+    }
+
+    /// Turned a quarter clockwise. Fixed flags travel with their cells in all the transforms below.
+    pub fn rotate90(&self) -> Board { self.remap(|r, c| (8 - c, r)) }
+
+    /// Rows become columns (mirrored on the main diagonal).
+    pub fn transpose(&self) -> Board { self.remap(|r, c| (c, r)) }
+
+    /// Flipped left to right.
+    pub fn mirror_horizontal(&self) -> Board { self.remap(|r, c| (r, 8 - c)) }
+
+    /// Every digit v replaced by `perm[v-1]`; blanks stay blank. A valid board stays valid when `perm` is a
+    /// permutation of 1..=9.
+    pub fn relabel(&self, perm: [u8; 9]) -> Board {
+        let mut b = self.clone();
+        b.invalidate_candidates();
+        for cell in b.cells.iter_mut().flatten() { if cell.value != 0 { cell.value = perm[cell.value as usize - 1]; } }
+        b
+    }
+
+    // New board whose (r,c) is this board's cell at `from(r,c)`
+    fn remap(&self, from: impl Fn(usize, usize) -> (usize, usize)) -> Board {
+        let mut b = Self::empty();
+        for r in 0..9 { for c in 0..9 { let (sr, sc) = from(r, c); b.cells[r][c] = self.cells[sr][sc]; } }
+        b
+    }
+
+    pub fn row_values(&self, r: usize) -> [u8; 9] { self.cells[r].map(|cell| cell.value) }
+    pub fn col_values(&self, c: usize) -> [u8; 9] { self.cells.map(|row| row[c].value) }
+    pub fn box_values(&self, br: usize, bc: usize) -> [u8; 9] { self.box_positions(br*3 + bc).map(|(r, c)| self.cells[r][c].value) }
+
+    /// Index (0..9) of the box holding (r,c). Box membership is only ever looked up through this and
+    /// `box_positions`, so a variant layout such as Jigsaw regions only has to change these two.
+    pub fn box_of(&self, r: usize, c: usize) -> usize { r/3*3 + c/3 }
+
+    /// Positions of box `idx` in reading order.
+    pub fn box_positions(&self, idx: usize) -> [(usize, usize); 9] { Unit::Box(idx).positions() }
+
+    pub fn row_cells(&self, r: usize) -> [Cell; 9] { self.cells[r] }
+    pub fn col_cells(&self, c: usize) -> [Cell; 9] { self.cells.map(|row| row[c]) }
+    pub fn box_cells(&self, br: usize, bc: usize) -> [Cell; 9] {
+        let mut a=[Cell::default();9];
+        for (i, (r, c)) in self.box_positions(br*3 + bc).into_iter().enumerate() { a[i]=self.cells[r][c]; }
         a
     }
 
+    /// The cells of `unit` with their positions.
+    pub fn unit(&self, unit: Unit) -> impl Iterator<Item = (usize, usize, &Cell)> {
+        unit.positions().into_iter().map(move |(r, c)| (r, c, &self.cells[r][c]))
+    }
+
     pub fn candidates(&self, r: usize, c: usize) -> [bool; 10] {
         // index 1..=9 true if allowed
-        if self.cells[r][c].value != 0 { let mut f=[false;10]; f[self.cells[r][c].value as usize]=true; return f; }
-        let mut forb=[false;10];
-        for x in self.row_values(r) { forb[x as usize]=true; }
-        for x in self.col_values(c) { forb[x as usize]=true; }
-        let br=r/3; let bc=c/3; for x in self.box_values(br,bc) { forb[x as usize]=true; }
-        let mut cand=[false;10];
-        for v in 1..=9 { cand[v as usize] = !forb[v as usize]; }
-        cand
+        let mask = if self.cands_valid { self.cands[r][c] } else { self.candidate_mask(r, c) };
+        std::array::from_fn(|v| v != 0 && mask & (1<<v) != 0)
     }
 
-    // Returns a mask of cells that are in conflict (duplicate non-zero values) in any row, column, or 3x3 box
-    pub fn conflict_mask(&self) -> [[bool; 9]; 9] {
-        let mut mask = [[false; 9]; 9];
+    // Uncached candidate computation; a filled cell only allows its own value
+    fn candidate_mask(&self, r: usize, c: usize) -> u16 {
+        if self.cells[r][c].value != 0 { return 1<<self.cells[r][c].value; }
+        let mut forb=0u16;
+        for x in self.row_values(r) { forb |= 1<<x; }
+        for x in self.col_values(c) { forb |= 1<<x; }
+        for (rr, cc) in self.box_positions(self.box_of(r, c)) { forb |= 1<<self.cells[rr][cc].value; }
+        0b11_1111_1110 & !forb
+    }
 
-        // Rows
-        for r in 0..9 {
-            let mut counts = [0u8; 10];
-            for c in 0..9 {
-                let v = self.cells[r][c].value as usize;
-                if v != 0 { counts[v] += 1; }
+    /// For each digit 1..=9, the first peer of (r,c) already holding it and the unit they share ("row", "col"
+    /// or "box"), or `None` if nothing rules the digit out. Peers are checked row first, then column, then box.
+    pub fn explain_cell(&self, r: usize, c: usize) -> Vec<(u8, Option<Blocker>)> {
+        let units = [(Unit::Row(r), "row"), (Unit::Col(c), "col"), (Unit::Box(self.box_of(r, c)), "box")];
+        (1..=9u8).map(|v| {
+            let blocker = units.iter().find_map(|&(u, name)| {
+                u.positions().into_iter().find(|&(pr, pc)| (pr, pc) != (r, c) && self.cells[pr][pc].value == v).map(|(pr, pc)| (pr, pc, name))
+            });
+            (v, blocker)
+        }).collect()
+    }
+
+    /// Rebuild the candidate cache from scratch; `candidates()` reads from it afterwards.
+    pub fn recompute_candidates(&mut self) {
+        for r in 0..9 { for c in 0..9 { self.cands[r][c] = self.candidate_mask(r, c); }}
+        self.cands_valid = true;
+    }
+
+    /// Rebuild the candidate cache and narrow each empty cell to `masks[r][c]` (bit v for digit v);
+    /// a mask of 0 leaves that cell as computed. Placing a digit with `set_value` keeps the narrowed sets, but
+    /// clearing or overwriting a cell recomputes it and its peers from the grid, dropping their restriction.
+    pub fn restrict_candidates(&mut self, masks: &[[u16; 9]; 9]) {
+        self.recompute_candidates();
+        for (r, row) in masks.iter().enumerate() { for (c, &m) in row.iter().enumerate() {
+            if self.cells[r][c].value == 0 && m != 0 { self.cands[r][c] &= m; }
+        }}
+    }
+
+    /// Drop the candidate cache, e.g. after writing to `cells` directly.
+    pub fn invalidate_candidates(&mut self) { self.cands_valid = false; }
+
+    /// Set a cell's value (0 clears it), keeping the candidate cache in sync when it is in use.
+    pub fn set_value(&mut self, r: usize, c: usize, v: u8) {
+        let old = self.cells[r][c].value;
+        self.cells[r][c].value = v;
+        if !self.cands_valid { return; }
+        if old == 0 && v != 0 {
+            // A placement only removes v from the peers
+            self.cands[r][c] = 1<<v;
+            for i in 0..9 {
+                if self.cells[r][i].value == 0 { self.cands[r][i] &= !(1<<v); }
+                if self.cells[i][c].value == 0 { self.cands[i][c] &= !(1<<v); }
             }
-            for c in 0..9 {
-                let v = self.cells[r][c].value as usize;
-                if v != 0 && counts[v] > 1 { mask[r][c] = true; }
+            for (rr, cc) in self.box_positions(self.box_of(r, c)) { if self.cells[rr][cc].value == 0 { self.cands[rr][cc] &= !(1<<v); } }
+        } else {
+            // Clearing or overwriting can give candidates back, so recompute the cell and its peers
+            for i in 0..9 {
+                self.cands[r][i] = self.candidate_mask(r, i);
+                self.cands[i][c] = self.candidate_mask(i, c);
             }
+            for (rr, cc) in self.box_positions(self.box_of(r, c)) { self.cands[rr][cc] = self.candidate_mask(rr, cc); }
         }
+    }
 
-        // Columns
-        for c in 0..9 {
-            let mut counts = [0u8; 10];
-            for r in 0..9 {
-                let v = self.cells[r][c].value as usize;
-                if v != 0 { counts[v] += 1; }
+    /// Debug view of every cell's candidates as a 3x3 mini-grid ('.' where ruled out); filled cells are blank.
+    pub fn candidates_pretty(&self) -> String {
+        let mut s = String::new();
+        for r in 0..9 {
+            if r > 0 && r % 3 == 0 { s.push_str(&"-".repeat(3*9 + 8 + 2*2)); s.push('\n'); }
+            for sub in 0..3 {
+                for c in 0..9 {
+                    if c > 0 { s.push_str(if c % 3 == 0 { " | " } else { " " }); }
+                    let cand = self.candidates(r, c);
+                    for (v, &ok) in cand.iter().enumerate().skip(sub*3+1).take(3) {
+                        s.push(if self.cells[r][c].value != 0 { ' ' } else if ok { char::from(b'0' + v as u8) } else { '.' });
+                    }
+                }
+                s.push('\n');
             }
-            for r in 0..9 {
+        }
+        s
+    }
+
+    // Returns a mask of cells that are in conflict (duplicate non-zero values) in any row, column, or 3x3 box
+    pub fn conflict_mask(&self) -> [[bool; 9]; 9] {
+        let mut mask = [[false; 9]; 9];
+        for unit in Unit::all() {
+            let counts = self.unit_counts(unit);
+            for (r, c) in unit.positions() {
                 let v = self.cells[r][c].value as usize;
                 if v != 0 && counts[v] > 1 { mask[r][c] = true; }
             }
         }
+        mask
+    }
 
-        // Boxes
-        for br in 0..3 {
-            for bc in 0..3 {
-                let mut counts = [0u8; 10];
-                for r in br*3..br*3+3 {
-                    for c in bc*3..bc*3+3 {
-                        let v = self.cells[r][c].value as usize;
-                        if v != 0 { counts[v] += 1; }
-                    }
-                }
-                for r in br*3..br*3+3 {
-                    for c in bc*3..bc*3+3 {
-                        let v = self.cells[r][c].value as usize;
-                        if v != 0 && counts[v] > 1 { mask[r][c] = true; }
-                    }
+    /// The rows, columns and boxes holding some digit more than once, in `Unit::all` order.
+    pub fn invalid_units(&self) -> Vec<Unit> {
+        Unit::all().filter(|&u| self.unit_counts(u).iter().skip(1).any(|&n| n > 1)).collect()
+    }
+
+    // How often each digit 1..=9 appears in the unit (index 0 unused)
+    fn unit_counts(&self, unit: Unit) -> [u8; 10] {
+        let mut counts = [0u8; 10];
+        for (r, c) in unit.positions() { counts[self.cells[r][c].value as usize] += 1; }
+        counts
+    }
+}
+
+/// Builds a board one given at a time, refusing any placement that clashes with its row, column or box
+/// right away instead of leaving it for `is_valid` to find.
+#[derive(Debug, Clone)]
+pub struct BoardBuilder {
+    board: Board,
+}
+
+impl Default for BoardBuilder {
+    fn default() -> Self { Self::new() }
+}
+
+impl BoardBuilder {
+    pub fn new() -> Self { Self { board: Board::empty() } }
+
+    /// Place given `v` (1..=9) at (r,c), replacing what was there, or clear the cell with 0.
+    pub fn set(&mut self, r: usize, c: usize, v: u8) -> anyhow::Result<&mut Self> {
+        if r >= 9 || c >= 9 { anyhow::bail!("cell ({}, {}) is off the board", r + 1, c + 1); }
+        if v > 9 { anyhow::bail!("{} is not a digit", v); }
+        if v != 0 {
+            let units = [(Unit::Row(r), "row"), (Unit::Col(c), "column"), (Unit::Box(self.board.box_of(r, c)), "box")];
+            for (unit, name) in units {
+                if let Some((pr, pc)) = unit.positions().into_iter().find(|&(pr, pc)| (pr, pc) != (r, c) && self.board.cells[pr][pc].value == v) {
+                    anyhow::bail!("{} at ({}, {}) clashes with ({}, {}) in the same {}", v, r + 1, c + 1, pr + 1, pc + 1, name);
                 }
             }
         }
+        self.board.cells[r][c] = Cell { value: v, fixed: v != 0 };
+        Ok(self)
+    }
 
-        mask
+    pub fn build(&self) -> Board { self.board.clone() }
+}
+
+/// A parsed `.sdk` file: the grid plus its `#` header metadata as (key, value) pairs in file order.
+#[derive(Debug, Clone)]
+pub struct SdkFile {
+    pub board: Board,
+    pub meta: Vec<(String, String)>,
+}
+
+/// Parse an `.sdk` file. Lines starting with `#` are metadata (`#A` author, `#S` source, `#C` comment,
+/// per the HoDoKu/SadMan convention; other tags keep their letter as key) and never count toward the grid.
+pub fn parse_sdk(text: &str) -> anyhow::Result<SdkFile> {
+    let mut meta = Vec::new();
+    let mut grid = String::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix('#') {
+            let mut chars = rest.chars();
+            let Some(tag) = chars.next() else { continue };
+            let key = match tag { 'A' => "author".to_string(), 'S' => "source".to_string(), 'C' => "comment".to_string(), t => t.to_string() };
+            meta.push((key, chars.as_str().trim().to_string()));
+        } else {
+            grid.push_str(line);
+            grid.push('\n');
+        }
     }
+    Ok(SdkFile { board: crate::io::parse_any(&grid)?, meta })
+}
+
+/// Cells whose value differs between `a` and `b`, as (r, c, old, new) in row-major order.
+pub fn diff(a: &Board, b: &Board) -> Vec<(usize, usize, u8, u8)> {
+    let mut out = Vec::new();
+    for r in 0..9 { for c in 0..9 {
+        let (old, new) = (a.cells[r][c].value, b.cells[r][c].value);
+        if old != new { out.push((r, c, old, new)); }
+    }}
+    out
 }
 
 fn no_dupes(vals: [u8;9]) -> bool {
@@ -139,7 +470,7 @@ impl Display for Board {
         for r in 0..9 {
             for c in 0..9 {
                 let v = self.cells[r][c].value;
-                write!(f, "{}{}", if v==0 {'.'} else { char::from(b'0'+v) }, if c%3==2 && c!=8 {' '} else { ' ' })?;
+                write!(f, "{} ", if v==0 {'.'} else { char::from(b'0'+v) })?;
             }
             if r%3==2 && r!=8 { writeln!(f)?; }
             writeln!(f)?;
@@ -0,0 +1,11 @@
+use crate::board::Board;
+
+/// Filled cells of `current` whose value differs from `solution`. Empty cells are never wrong.
+pub fn wrong_cells(current: &Board, solution: &Board) -> Vec<(usize, usize)> {
+    let mut out = Vec::new();
+    for r in 0..9 { for c in 0..9 {
+        let v = current.cells[r][c].value;
+        if v != 0 && v != solution.cells[r][c].value { out.push((r, c)); }
+    }}
+    out
+}
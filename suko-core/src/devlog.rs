@@ -4,15 +4,23 @@ use std::fs::{self, OpenOptions};
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use serde::{Serialize, Deserialize};
-use crate::solver::Step;
+use crate::board::{diff, Board};
+use crate::solver::{Step, StepKind};
 
 pub struct DevLogger {
     root: PathBuf,
     index: usize,
+    // Append every entry to one devlog.txt instead of numbered files
+    single_file: bool,
+    // Count entries without touching the disk (see `null`)
+    disabled: bool,
 }
 
 impl DevLogger {
-    pub fn new(root: impl Into<PathBuf>) -> std::io::Result<Self> {
+    pub fn new(root: impl Into<PathBuf>) -> std::io::Result<Self> { Self::with_single_file(root, false) }
+
+    /// Like `new`; with `single_file` set, all entries go into `devlog.txt` separated by a rule.
+    pub fn with_single_file(root: impl Into<PathBuf>, single_file: bool) -> std::io::Result<Self> {
         let root = root.into();
         fs::create_dir_all(&root)?;
         // Determine next index by scanning existing files
@@ -20,17 +28,32 @@ impl DevLogger {
         if let Ok(rd) = fs::read_dir(&root) { for e in rd.flatten() { if let Some(name)=e.file_name().to_str() {
             if let Some(num) = name.strip_prefix("devlog").and_then(|s| s.strip_suffix(".txt")).and_then(|n| n.parse::<usize>().ok()) { if num>max_idx { max_idx=num; } }
         }}}
-        Ok(Self { root, index: max_idx })
+        Ok(Self { root, index: max_idx, single_file, disabled: false })
     }
 
+    /// A logger that only counts entries: `write_log` does no file I/O and returns the path it would have used.
+    pub fn null() -> Self { Self { root: PathBuf::new(), index: 0, single_file: false, disabled: true } }
+
+    /// Number of the last numbered entry, counting ones found on disk at startup.
+    pub fn index(&self) -> usize { self.index }
+
     pub fn next_file(&mut self) -> PathBuf {
         self.index += 1;
         self.root.join(format!("devlog{}.txt", self.index))
     }
 
     pub fn write_log(&mut self, title: &str, lines: &[impl AsRef<str>]) -> std::io::Result<PathBuf> {
-        let path = self.next_file();
-        let mut f = OpenOptions::new().create(true).write(true).open(&path)?;
+        if self.disabled { return Ok(self.next_file()); }
+        let (path, mut f) = if self.single_file {
+            let path = self.root.join("devlog.txt");
+            let mut f = OpenOptions::new().create(true).append(true).open(&path)?;
+            if f.metadata()?.len() > 0 { writeln!(f, "\n========================================\n")?; }
+            (path, f)
+        } else {
+            let path = self.next_file();
+            let f = OpenOptions::new().create(true).write(true).truncate(true).open(&path)?;
+            (path, f)
+        };
         let ts_fmt = format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
         let now = OffsetDateTime::now_utc().format(&ts_fmt).unwrap_or_else(|_| "unknown".into());
         writeln!(f, "{}", title)?;
@@ -47,27 +70,99 @@ pub struct SessionLog {
     pub puzzle: String,
     pub solver_name: String,
     pub steps: Vec<Step>,
+    // Wall-clock time from the first step to the last; older logs without it read as 0
+    #[serde(default)]
+    pub duration_ms: u128,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StepCounts {
+    pub placements: usize,
+    pub eliminations: usize,
+    pub guesses: usize,
+    pub backtracks: usize,
 }
 
+impl SessionLog {
+    pub fn step_counts(&self) -> StepCounts {
+        let mut counts = StepCounts::default();
+        for s in &self.steps {
+            match s.kind {
+                crate::solver::StepKind::Place{ .. } => counts.placements += 1,
+                crate::solver::StepKind::Guess{ .. } => counts.guesses += 1,
+                crate::solver::StepKind::Backtrack => counts.backtracks += 1,
+                crate::solver::StepKind::Eliminate{ .. } => counts.eliminations += 1,
+            }
+        }
+        counts
+    }
+
+    /// Encode for the `.suko` session file. Unlike the markdown export this can be loaded back and replayed.
+    pub fn to_bytes(&self) -> Vec<u8> { serde_json::to_vec(self).expect("session serializes") }
+
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> { Ok(serde_json::from_slice(bytes)?) }
+
+    /// One-line summary, e.g. "12 placements, 3 guesses, 1 backtrack, solved in 45ms".
+    /// Eliminations are listed after placements when there are any.
+    pub fn summary(&self) -> String {
+        fn plural(n: usize, one: &str, many: &str) -> String { format!("{} {}", n, if n == 1 { one } else { many }) }
+        let counts = self.step_counts();
+        let solved = self.steps.last().is_some_and(|s| s.board.is_solved());
+        let elims = if counts.eliminations > 0 { format!(", {}", plural(counts.eliminations, "elimination", "eliminations")) } else { String::new() };
+        format!("{}{}, {}, {}, {} in {}ms",
+            plural(counts.placements, "placement", "placements"), elims, plural(counts.guesses, "guess", "guesses"),
+            plural(counts.backtracks, "backtrack", "backtracks"),
+            if solved { "solved" } else { "stopped" }, self.duration_ms)
+    }
+}
+
+/// Save a session in the reloadable `.suko` format (see `SessionLog::to_bytes`).
+pub fn write_session_file<P: AsRef<Path>>(path: P, log: &SessionLog) -> std::io::Result<()> { fs::write(path, log.to_bytes()) }
+
+pub fn read_session_file<P: AsRef<Path>>(path: P) -> anyhow::Result<SessionLog> { SessionLog::from_bytes(&fs::read(path)?) }
+
 pub fn write_session_markdown<P: AsRef<Path>>(dir: P, log: &SessionLog) -> std::io::Result<PathBuf> {
     fs::create_dir_all(dir.as_ref())?;
     let ts_fmt = format_description!("[year]-[month]-[day]_[hour][minute][second]");
     let now = OffsetDateTime::now_utc().format(&ts_fmt).unwrap_or_else(|_| "now".into());
     let filename = format!("session_{}_.md", now);
     let path = dir.as_ref().join(filename);
-    let mut f = OpenOptions::new().create(true).write(true).open(&path)?;
+    let mut f = OpenOptions::new().create(true).write(true).truncate(true).open(&path)?;
     writeln!(f, "# {}", log.title)?;
     writeln!(f, "Solver: {}", log.solver_name)?;
     writeln!(f, "Puzzle: `{}`", log.puzzle)?;
+    writeln!(f, "\n## Summary\n{}", log.summary())?;
     writeln!(f, "\n## Steps")?;
     for s in &log.steps {
         writeln!(f, "\n### Step {}", s.index)?;
         match &s.kind { 
-            crate::solver::StepKind::Place{ r,c,v,reason } => writeln!(f, "- Place {} at ({}, {}) — {}", v, r+1, c+1, reason)?,
+            crate::solver::StepKind::Place{ r,c,v,reason, .. } => writeln!(f, "- Place {} at ({}, {}) — {}", v, r+1, c+1, reason)?,
             crate::solver::StepKind::Guess{ r,c,v } => writeln!(f, "- Guess {} at ({}, {})", v, r+1, c+1)?,
             crate::solver::StepKind::Backtrack => writeln!(f, "- Backtrack")?,
+            crate::solver::StepKind::Eliminate{ r,c,v,reason } => writeln!(f, "- Eliminate {} from ({}, {}) — {}", v, r+1, c+1, reason)?,
         }
         writeln!(f, "\n``\n{}\n``", s.board)?;
     }
     Ok(path)
 }
+
+/// The session as plain-text frames separated by form feeds (`\x0c`): the starting puzzle, then the board
+/// after each step, captioned with the step and the cells it changed. Paging through the output replays
+/// the solve.
+pub fn animate_session(log: &SessionLog) -> String {
+    let mut prev = Board::parse(&log.puzzle).unwrap_or_else(|_| Board::empty());
+    let mut frames = vec![format!("Start\n{}", prev)];
+    for s in &log.steps {
+        let what = match &s.kind {
+            StepKind::Place{ r,c,v,reason, .. } => format!("Place {} at ({}, {}) — {}", v, r+1, c+1, reason),
+            StepKind::Guess{ r,c,v } => format!("Guess {} at ({}, {})", v, r+1, c+1),
+            StepKind::Backtrack => "Backtrack".to_string(),
+            StepKind::Eliminate{ r,c,v,reason } => format!("Eliminate {} from ({}, {}) — {}", v, r+1, c+1, reason),
+        };
+        let changed: Vec<String> = diff(&prev, &s.board).into_iter().map(|(r, c, from, to)| format!("({}, {}) {} -> {}", r+1, c+1, from, to)).collect();
+        let changed = if changed.is_empty() { "none".to_string() } else { changed.join(", ") };
+        frames.push(format!("Step {}: {}\nChanged: {}\n{}", s.index, what, changed, s.board));
+        prev = s.board.clone();
+    }
+    frames.join("\x0c")
+}
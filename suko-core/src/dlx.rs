@@ -0,0 +1,146 @@
+use crate::board::Board;
+
+/// Exact-cover solver (Knuth's Algorithm X with dancing links) over the 324 Sudoku constraints: every cell
+/// filled once, and every digit once per row, column and box. Much faster than the backtrackers when a
+/// search has to run to the end, as counting solutions does.
+pub struct DlxSolver;
+impl DlxSolver {
+    /// The first solution found, or None if the givens conflict or the grid has no solution.
+    pub fn solve(board: &Board) -> Option<Board> {
+        let mut m = Matrix::for_board(board);
+        let mut partial = Vec::new();
+        let mut first = None;
+        m.search(&mut partial, &mut 0, 1, &mut first);
+        let rows = first?;
+        let mut b = board.clone();
+        for id in rows { b.set_value(id / 81, id / 9 % 9, (id % 9) as u8 + 1); }
+        Some(b)
+    }
+
+    /// Number of solutions, counting stops at `limit`.
+    pub fn count(board: &Board, limit: usize) -> usize {
+        if limit == 0 { return 0; }
+        let mut m = Matrix::for_board(board);
+        let mut count = 0;
+        m.search(&mut Vec::new(), &mut count, limit, &mut None);
+        count
+    }
+}
+
+// Toroidal doubly-linked matrix stored as parallel index arrays. Node 0 is the root, 1..=324 the column
+// headers; each candidate row "digit v at (r,c)" adds four nodes and carries the id r*81 + c*9 + (v-1).
+struct Matrix {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    col: Vec<usize>,
+    row: Vec<usize>,
+    size: Vec<usize>,
+}
+
+const COLUMNS: usize = 324;
+
+impl Matrix {
+    fn for_board(board: &Board) -> Self {
+        let n = COLUMNS + 1;
+        let mut m = Matrix {
+            left: (0..n).map(|i| if i == 0 { COLUMNS } else { i - 1 }).collect(),
+            right: (0..n).map(|i| (i + 1) % n).collect(),
+            up: (0..n).collect(),
+            down: (0..n).collect(),
+            col: (0..n).collect(),
+            row: vec![usize::MAX; n],
+            size: vec![0; n],
+        };
+        // A given only offers its own digit, so conflicting givens simply leave no exact cover
+        for r in 0..9 { for c in 0..9 {
+            let given = board.cells[r][c].value as usize;
+            for v in 1..=9 {
+                if given != 0 && v != given { continue; }
+                let bx = r / 3 * 3 + c / 3;
+                let cols = [r * 9 + c, 81 + r * 9 + v - 1, 162 + c * 9 + v - 1, 243 + bx * 9 + v - 1];
+                m.add_row(r * 81 + c * 9 + v - 1, cols.map(|x| x + 1));
+            }
+        }}
+        m
+    }
+
+    fn add_row(&mut self, id: usize, cols: [usize; 4]) {
+        let first = self.col.len();
+        for (i, &c) in cols.iter().enumerate() {
+            let node = first + i;
+            // Append at the bottom of column c
+            self.up.push(self.up[c]);
+            self.down.push(c);
+            let above = self.up[c];
+            self.down[above] = node;
+            self.up[c] = node;
+            self.left.push(if i == 0 { first + 3 } else { node - 1 });
+            self.right.push(if i == 3 { first } else { node + 1 });
+            self.col.push(c);
+            self.row.push(id);
+            self.size[c] += 1;
+        }
+    }
+
+    fn cover(&mut self, c: usize) {
+        self.right[self.left[c]] = self.right[c];
+        self.left[self.right[c]] = self.left[c];
+        let mut i = self.down[c];
+        while i != c {
+            let mut j = self.right[i];
+            while j != i {
+                self.down[self.up[j]] = self.down[j];
+                self.up[self.down[j]] = self.up[j];
+                self.size[self.col[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, c: usize) {
+        let mut i = self.up[c];
+        while i != c {
+            let mut j = self.left[i];
+            while j != i {
+                self.size[self.col[j]] += 1;
+                self.down[self.up[j]] = j;
+                self.up[self.down[j]] = j;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+        self.right[self.left[c]] = c;
+        self.left[self.right[c]] = c;
+    }
+
+    // Count exact covers up to `limit`, keeping the row ids of the first one in `first`
+    fn search(&mut self, partial: &mut Vec<usize>, count: &mut usize, limit: usize, first: &mut Option<Vec<usize>>) {
+        if self.right[0] == 0 {
+            *count += 1;
+            if first.is_none() { *first = Some(partial.clone()); }
+            return;
+        }
+        // Column with the fewest rows left, as MRV does for cells
+        let mut c = self.right[0];
+        let mut j = self.right[c];
+        while j != 0 { if self.size[j] < self.size[c] { c = j; } j = self.right[j]; }
+        if self.size[c] == 0 { return; }
+
+        self.cover(c);
+        let mut r = self.down[c];
+        while r != c && *count < limit {
+            partial.push(self.row[r]);
+            let mut j = self.right[r];
+            while j != r { self.cover(self.col[j]); j = self.right[j]; }
+            self.search(partial, count, limit, first);
+            let mut j = self.left[r];
+            while j != r { self.uncover(self.col[j]); j = self.left[j]; }
+            partial.pop();
+            r = self.down[r];
+        }
+        self.uncover(c);
+    }
+}
@@ -7,16 +7,26 @@ pub struct HighscoreEntry {
     #[serde(default, deserialize_with = "deserialize_opt_string_from_any")]
     pub seed: Option<String>,
     pub clues: Option<usize>,
+    #[serde(default)]
     pub date_utc: String,
     // If no seed was used, store the finished 81-char grid so it can be reloaded
     pub solution_sdk: Option<String>,
 }
 
 pub fn load<P: AsRef<Path>>(path: P) -> Vec<HighscoreEntry> {
-    match fs::read_to_string(path) {
-        Ok(s) => serde_json::from_str::<Vec<HighscoreEntry>>(&s).unwrap_or_default(),
-        Err(_) => Vec::new(),
-    }
+    load_lenient(path).0
+}
+
+/// Read the list entry by entry: entries that don't parse (e.g. no `time_ms`) are skipped and counted
+/// instead of discarding the whole file, and missing optional fields take their defaults. A missing file
+/// or one that isn't a JSON array gives an empty list with nothing skipped.
+pub fn load_lenient<P: AsRef<Path>>(path: P) -> (Vec<HighscoreEntry>, usize) {
+    let Ok(s) = fs::read_to_string(path) else { return (Vec::new(), 0) };
+    let Ok(serde_json::Value::Array(items)) = serde_json::from_str(&s) else { return (Vec::new(), 0) };
+    let total = items.len();
+    let list: Vec<HighscoreEntry> = items.into_iter().filter_map(|v| serde_json::from_value(v).ok()).collect();
+    let skipped = total - list.len();
+    (list, skipped)
 }
 
 pub fn save<P: AsRef<Path>>(path: P, list: &[HighscoreEntry]) -> std::io::Result<()> {
@@ -24,6 +34,29 @@ pub fn save<P: AsRef<Path>>(path: P, list: &[HighscoreEntry]) -> std::io::Result
     fs::write(path, json)
 }
 
+/// Add `entry` unless it repeats a puzzle already on the list, in which case only the faster time is kept.
+/// Seeded entries repeat when seed and clue count match; seedless ones when their finished grids are identical.
+pub fn insert_dedup(list: &mut Vec<HighscoreEntry>, entry: HighscoreEntry) {
+    let same = |e: &HighscoreEntry| match (&entry.seed, &e.seed) {
+        (Some(s), Some(t)) => s == t && entry.clues == e.clues,
+        (None, None) => entry.solution_sdk.is_some() && entry.solution_sdk == e.solution_sdk,
+        _ => false,
+    };
+    match list.iter().position(same) {
+        Some(i) => if entry.time_ms < list[i].time_ms { list[i] = entry; },
+        None => list.push(entry),
+    }
+}
+
+/// The `n` fastest entries played at exactly `clues` clues, fastest first; equal times keep the earlier date
+/// first. Entries without a clue count never match a filter.
+pub fn top_n_by_clues(list: &[HighscoreEntry], clues: usize, n: usize) -> Vec<&HighscoreEntry> {
+    let mut out: Vec<&HighscoreEntry> = list.iter().filter(|e| e.clues == Some(clues)).collect();
+    out.sort_by(|a, b| a.time_ms.cmp(&b.time_ms).then_with(|| a.date_utc.cmp(&b.date_utc)));
+    out.truncate(n);
+    out
+}
+
 fn deserialize_opt_string_from_any<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
 where
     D: Deserializer<'de>,
@@ -0,0 +1,11 @@
+use rand::{seq::SliceRandom, Rng};
+use crate::board::Board;
+
+/// A random empty cell of `current` with its value from `solution`, or `None` once no cell is empty.
+/// Cells the player already filled, right or wrong, are never picked.
+pub fn reveal_one<R: Rng>(current: &Board, solution: &Board, rng: &mut R) -> Option<(usize, usize, u8)> {
+    let mut empties = Vec::new();
+    for r in 0..9 { for c in 0..9 { if current.cells[r][c].value == 0 { empties.push((r, c)); } } }
+    let &(r, c) = empties.choose(rng)?;
+    Some((r, c, solution.cells[r][c].value))
+}
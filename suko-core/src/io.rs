@@ -0,0 +1,30 @@
+use crate::board::Board;
+
+/// Parse a grid in any of the common text layouts: a bare 81-character line, nine lines of nine, or the
+/// "pretty" SadMan/SimpleSudoku layout framed with `|`, `+`, `-` (and `*` corners). Blanks may be written
+/// as `.`, `0`, `_`, `*`, `-`, `x` or `?`. Lines starting with `#` are comments.
+pub fn parse_any(text: &str) -> anyhow::Result<Board> {
+    let mut digits = String::with_capacity(81);
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || is_separator(line) { continue; }
+        for ch in line.chars() {
+            match ch {
+                '1'..='9' => digits.push(ch),
+                '.' | '0' | '_' | '*' | '-' | 'x' | 'X' | '?' => digits.push('.'),
+                '|' | '+' => {}
+                ch if ch.is_whitespace() => {}
+                ch => anyhow::bail!("unexpected character '{}' in grid", ch),
+            }
+        }
+    }
+    if digits.len() != 81 { anyhow::bail!("expected 81 cells, got {}", digits.len()); }
+    Board::parse(&digits)
+}
+
+// Box borders such as "---+---+---", "|-----------|" or "*-----------*". A line of exactly nine '-'/'*'
+// without '+' or '=' ("---------", "--- | --- | ---") is a row of blanks instead.
+fn is_separator(line: &str) -> bool {
+    if !line.contains(['-', '=']) || !line.chars().all(|ch| "-=+|*".contains(ch) || ch.is_whitespace()) { return false; }
+    line.contains(['+', '=']) || line.chars().filter(|&ch| ch == '-' || ch == '*').count() != 9
+}
@@ -0,0 +1,62 @@
+use crate::board::Board;
+
+/// A Killer Sudoku cage: its cells hold distinct digits adding up to `sum`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cage {
+    pub cells: Vec<(usize, usize)>,
+    pub sum: u8,
+}
+
+/// A standard board plus cages. The plain Sudoku rules still apply; the cages only add constraints.
+#[derive(Debug, Clone)]
+pub struct KillerBoard {
+    pub board: Board,
+    pub cages: Vec<Cage>,
+}
+
+impl KillerBoard {
+    pub fn new(board: Board, cages: Vec<Cage>) -> Self { Self { board, cages } }
+
+    /// The cage containing (r,c), if any.
+    pub fn cage_of(&self, r: usize, c: usize) -> Option<&Cage> { self.cages.iter().find(|cage| cage.cells.contains(&(r, c))) }
+
+    /// `Board::is_valid`, and no cage repeats a digit, goes over its sum, or is full without reaching it.
+    pub fn is_valid(&self) -> bool {
+        if !self.board.is_valid() { return false; }
+        self.cages.iter().all(|cage| {
+            let (used, total, empty) = self.cage_state(cage);
+            let distinct = used.count_ones() as usize == cage.cells.len() - empty;
+            distinct && total <= cage.sum as u32 && (empty > 0 || total == cage.sum as u32)
+        })
+    }
+
+    /// `Board::candidates`, minus digits that leave the cell's cage unable to reach its sum with distinct
+    /// digits in its other empty cells.
+    pub fn candidates(&self, r: usize, c: usize) -> [bool; 10] {
+        let mut cand = self.board.candidates(r, c);
+        let Some(cage) = self.cage_of(r, c) else { return cand };
+        if self.board.cells[r][c].value != 0 { return cand; }
+        let (used, total, empty) = self.cage_state(cage);
+        let left = cage.sum as i32 - total as i32;
+        for (v, ok) in cand.iter_mut().enumerate().skip(1) {
+            if *ok && (used & (1 << v) != 0 || !can_make(left - v as i32, empty - 1, used | (1 << v), 1)) { *ok = false; }
+        }
+        cand
+    }
+
+    // Digits placed in the cage as a bit mask, their sum, and how many cells are still empty
+    fn cage_state(&self, cage: &Cage) -> (u16, u32, usize) {
+        let (mut used, mut total, mut empty) = (0u16, 0u32, 0usize);
+        for &(r, c) in &cage.cells {
+            let v = self.board.cells[r][c].value;
+            if v == 0 { empty += 1; } else { used |= 1 << v; total += v as u32; }
+        }
+        (used, total, empty)
+    }
+}
+
+// Whether `count` distinct digits from `from..=9`, none in `used`, add up to `sum`
+fn can_make(sum: i32, count: usize, used: u16, from: i32) -> bool {
+    if count == 0 { return sum == 0; }
+    (from..=9).any(|d| d <= sum && used & (1 << d) == 0 && can_make(sum - d, count - 1, used, d + 1))
+}
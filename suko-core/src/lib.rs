@@ -3,3 +3,13 @@ pub mod devlog;
 pub mod solver;
 pub mod puzzle;
 pub mod highscores;
+pub mod io;
+pub mod maze;
+pub mod check;
+pub mod library;
+pub mod packed;
+pub mod batch;
+pub mod hint;
+pub mod dlx;
+pub mod killer;
+pub mod verify;
@@ -0,0 +1,26 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use crate::board::{parse_sdk, Board};
+
+/// Every `.sdk` or `.txt` puzzle in `dir` as (file name, board), sorted by name. Files that can't be read
+/// or don't hold a grid are skipped; only a missing or unreadable directory is an error.
+pub fn load_dir<P: AsRef<Path>>(dir: P) -> anyhow::Result<Vec<(String, Board)>> {
+    let mut out = Vec::new();
+    for e in fs::read_dir(dir)?.flatten() {
+        let path = e.path();
+        let is_puzzle = path.extension().and_then(|x| x.to_str()).is_some_and(|x| x.eq_ignore_ascii_case("sdk") || x.eq_ignore_ascii_case("txt"));
+        if !is_puzzle { continue; }
+        let Some(name) = path.file_name().and_then(|n| n.to_str()).map(str::to_string) else { continue };
+        let Ok(text) = fs::read_to_string(&path) else { continue };
+        if let Ok(sdk) = parse_sdk(&text) { out.push((name, sdk.board)); }
+    }
+    out.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(out)
+}
+
+/// Drop puzzles whose grid already appeared earlier in `list` (same values, whatever the file format),
+/// keeping the first of each.
+pub fn dedup(list: &mut Vec<(String, Board)>) {
+    let mut seen = HashSet::new();
+    list.retain(|(_, b)| seen.insert(b.content_hash()));
+}
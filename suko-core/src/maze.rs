@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use rand::seq::SliceRandom;
 use rand::{Rng, SeedableRng};
 
@@ -49,7 +50,58 @@ impl Maze {
         maze
     }
 
-    pub fn to_ascii(&self) -> String {
+    /// Occupancy grid of `(2*height+1)` rows by `(2*width+1)` columns, indexed `[y][x]`: 0 = open, 1 = wall.
+    /// Cell (x,y) sits at `[2*y+1][2*x+1]`; the positions between cells are open where a passage was carved.
+    pub fn to_grid_bytes(&self) -> Vec<Vec<u8>> {
+        let mut g = vec![vec![1u8; 2*self.width+1]; 2*self.height+1];
+        for y in 0..self.height { for x in 0..self.width {
+            let c = self.grid[self.idx(x,y)];
+            let (gy, gx) = (2*y+1, 2*x+1);
+            g[gy][gx] = 0;
+            if !c.walls[0] { g[gy-1][gx] = 0; }
+            if !c.walls[1] { g[gy][gx+1] = 0; }
+            if !c.walls[2] { g[gy+1][gx] = 0; }
+            if !c.walls[3] { g[gy][gx-1] = 0; }
+        }}
+        g
+    }
+
+    /// Shortest path of cells from `start` to `goal` through carved passages, both ends included.
+    /// None if either end is outside the maze or the goal can't be reached.
+    pub fn solve(&self, start: (usize,usize), goal: (usize,usize)) -> Option<Vec<(usize,usize)>> {
+        let inside = |(x,y): (usize,usize)| x < self.width && y < self.height;
+        if !inside(start) || !inside(goal) { return None; }
+        // BFS; prev[i] is the cell we reached cell i from
+        let mut prev: Vec<Option<usize>> = vec![None; self.grid.len()];
+        let mut seen = vec![false; self.grid.len()];
+        let mut queue = VecDeque::new();
+        seen[self.idx(start.0, start.1)] = true;
+        queue.push_back(start);
+        while let Some((x,y)) = queue.pop_front() {
+            if (x,y) == goal { break; }
+            let walls = self.grid[self.idx(x,y)].walls;
+            let moves = [(0, y > 0, (x, y.wrapping_sub(1))), (1, true, (x+1, y)), (2, true, (x, y+1)), (3, x > 0, (x.wrapping_sub(1), y))];
+            for (dir, ok, next) in moves {
+                if !ok || walls[dir] || !inside(next) { continue; }
+                let ni = self.idx(next.0, next.1);
+                if seen[ni] { continue; }
+                seen[ni] = true;
+                prev[ni] = Some(self.idx(x,y));
+                queue.push_back(next);
+            }
+        }
+        if !seen[self.idx(goal.0, goal.1)] { return None; }
+        let mut path = vec![goal];
+        let mut cur = self.idx(goal.0, goal.1);
+        while let Some(p) = prev[cur] { path.push((p % self.width, p / self.width)); cur = p; }
+        path.reverse();
+        Some(path)
+    }
+
+    pub fn to_ascii(&self) -> String { self.to_ascii_with_path(&[]) }
+
+    /// Like `to_ascii`, with the cells on `path` marked `*`.
+    pub fn to_ascii_with_path(&self, path: &[(usize,usize)]) -> String {
         // Each cell -> 2x1 chars horizontally for walls; draw top border and rows
         let mut s = String::new();
         // top border
@@ -62,7 +114,7 @@ impl Maze {
             let mut line2 = String::from("+");
             for x in 0..self.width {
                 let c = self.grid[self.idx(x,y)];
-                line1.push_str("  ");
+                line1.push_str(if path.contains(&(x,y)) { " *" } else { "  " });
                 line1.push(if c.walls[1] { '|' } else { ' ' });
                 line2.push_str(if c.walls[2] { "--" } else { "  " });
                 line2.push('+');
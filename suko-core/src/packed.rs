@@ -0,0 +1,55 @@
+use crate::board::Board;
+
+/// A grid as 81 bytes in row-major order, 0 for blanks. Apart from the `Board` conversions it only needs `core`,
+/// for embedding where `Board` and its serde support aren't wanted. Given/user distinctions are not kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PackedBoard(pub [u8; 81]);
+
+impl PackedBoard {
+    pub fn from_board(b: &Board) -> Self {
+        let mut cells = [0u8; 81];
+        for r in 0..9 { for c in 0..9 { cells[r*9 + c] = b.cells[r][c].value; }}
+        Self(cells)
+    }
+
+    /// Back to a `Board`, with every filled cell marked as a given.
+    pub fn to_board(&self) -> Board {
+        let mut rows = [[0u8; 9]; 9];
+        for i in 0..81 { rows[i/9][i%9] = self.0[i]; }
+        Board::from_rows(rows)
+    }
+
+    pub fn get(&self, r: usize, c: usize) -> u8 { self.0[r*9 + c] }
+
+    /// No digit repeats in any row, column or box; blanks are ignored.
+    pub fn is_valid(&self) -> bool {
+        for u in 0..9 {
+            let (mut row, mut col, mut bx) = (0u16, 0u16, 0u16);
+            for i in 0..9 {
+                let (br, bc) = (u/3*3 + i/3, u%3*3 + i%3);
+                for (seen, v) in [(&mut row, self.get(u, i)), (&mut col, self.get(i, u)), (&mut bx, self.get(br, bc))] {
+                    if v == 0 { continue; }
+                    if *seen & (1<<v) != 0 { return false; }
+                    *seen |= 1<<v;
+                }
+            }
+        }
+        true
+    }
+
+    pub fn is_solved(&self) -> bool { self.0.iter().all(|&v| v != 0) && self.is_valid() }
+
+    /// Digits allowed at (r,c) as a bit mask (bit v set for digit v), same as `Board::candidates`:
+    /// a filled cell only allows its own value.
+    pub fn candidates(&self, r: usize, c: usize) -> u16 {
+        let v = self.get(r, c);
+        if v != 0 { return 1<<v; }
+        let mut forb = 0u16;
+        for i in 0..9 {
+            forb |= 1 << self.get(r, i);
+            forb |= 1 << self.get(i, c);
+            forb |= 1 << self.get(r/3*3 + i/3, c/3*3 + i%3);
+        }
+        0b11_1111_1110 & !forb
+    }
+}
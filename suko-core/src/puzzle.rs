@@ -1,6 +1,138 @@
 use rand::{seq::SliceRandom, SeedableRng};
 
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+
 use crate::board::Board;
+use crate::solver::{mrv, LogicalSolver, Solver, Step, StepKind, Technique};
+
+/// How hard a puzzle is, judged by the hardest technique the logical solver needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Difficulty {
+    /// Naked and hidden singles only
+    Easy,
+    /// Needs locked candidates (pointing/claiming)
+    Medium,
+    /// Needs naked pairs or fish/wing/coloring patterns
+    Hard,
+    /// Logic gets stuck; backtracking is required
+    Expert,
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let s = match self { Difficulty::Easy => "Easy", Difficulty::Medium => "Medium", Difficulty::Hard => "Hard", Difficulty::Expert => "Expert" };
+        write!(f, "{}", s)
+    }
+}
+
+/// Symmetry kept between removed clues in `generate_symmetric_puzzle`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Symmetry {
+    /// 180° rotation about the centre, as used by most publications
+    Rotational180,
+    /// Mirror across the middle row
+    Horizontal,
+    /// Mirror across the middle column
+    Vertical,
+    /// Mirror across the main diagonal
+    Diagonal,
+}
+
+impl Symmetry {
+    /// The cell paired with (r,c); may be the cell itself on the axis/centre
+    pub fn partner(self, r: usize, c: usize) -> (usize, usize) {
+        match self {
+            Symmetry::Rotational180 => (8 - r, 8 - c),
+            Symmetry::Horizontal => (8 - r, c),
+            Symmetry::Vertical => (r, 8 - c),
+            Symmetry::Diagonal => (c, r),
+        }
+    }
+}
+
+/// Whether `board` has a unique solution and every given is needed for it: clearing any one of them lets
+/// a second solution in. Puzzles with several solutions to begin with are not minimal.
+pub fn is_minimal(board: &Board) -> bool {
+    if count_solutions_board(board, 2) != 1 { return false; }
+    let mut b = board.clone();
+    for r in 0..9 { for c in 0..9 {
+        let v = b.cells[r][c].value;
+        if v == 0 { continue; }
+        b.cells[r][c].value = 0;
+        let mut copy = b.clone();
+        let redundant = count_solutions(&mut copy, 2) == 1;
+        b.cells[r][c].value = v;
+        if redundant { return false; }
+    }}
+    true
+}
+
+/// Whether the logical solver, with every technique it knows, fills the whole grid without guessing.
+pub fn solvable_logically(board: &Board) -> bool { logical_run(board).1 }
+
+/// Rate a puzzle by solving a copy with the logical solver and taking the hardest technique it used.
+pub fn rate_difficulty(board: &Board) -> Difficulty {
+    let (steps, solved) = logical_run(board);
+    if !solved { return Difficulty::Expert; }
+    techniques(&steps).map(difficulty_of).max().unwrap_or(Difficulty::Easy)
+}
+
+/// Count how many placements each logical technique made while solving a copy of `board`, keyed by the
+/// technique's name ("Naked single", "X-Wing", ...).
+/// Returns an empty map and `true` when logic gets stuck and backtracking would be required.
+pub fn difficulty_profile(board: &Board) -> (HashMap<String, usize>, bool) {
+    let (steps, solved) = logical_run(board);
+    if !solved { return (HashMap::new(), true); }
+    let mut profile = HashMap::new();
+    for t in techniques(&steps) { *profile.entry(t.to_string()).or_insert(0) += 1; }
+    (profile, false)
+}
+
+/// A continuous difficulty score: solve a copy logically and add up a weight per placement by technique
+/// (naked single 1, hidden single 2, locked candidates 5, naked pair 8, ... X-Wing 20), plus 100 if logic
+/// gets stuck and search would have to finish the job. Handy for sorting a puzzle collection.
+pub fn difficulty_score(board: &Board) -> u32 {
+    let (steps, solved) = logical_run(board);
+    let logic: u32 = techniques(&steps).map(weight_of).sum();
+    logic + if solved { 0 } else { 100 }
+}
+
+// Solve a copy with every technique: the steps, and whether logic alone filled the grid
+fn logical_run(board: &Board) -> (Vec<Step>, bool) {
+    let steps = LogicalSolver::new().solve_steps(board, None);
+    let solved = steps.last().map_or_else(|| board.is_solved(), |s| s.board.is_solved());
+    (steps, solved)
+}
+
+fn techniques(steps: &[Step]) -> impl Iterator<Item = Technique> + '_ {
+    steps.iter().filter_map(|s| match s.kind { StepKind::Place{ technique, .. } => Some(technique), _ => None })
+}
+
+fn weight_of(t: Technique) -> u32 {
+    match t {
+        Technique::NakedSingle => 1,
+        Technique::HiddenSingle => 2,
+        Technique::LockedCandidates => 5,
+        Technique::NakedPair => 8,
+        Technique::HiddenPair => 10,
+        Technique::NakedTriple => 12,
+        Technique::HiddenTriple => 14,
+        Technique::NakedQuad => 16,
+        Technique::XWing => 20,
+        Technique::Swordfish | Technique::XyWing | Technique::WWing => 25,
+        Technique::Jellyfish | Technique::SimpleColoring | Technique::RemotePair | Technique::UniqueRectangle => 30,
+    }
+}
+
+fn difficulty_of(t: Technique) -> Difficulty {
+    match t {
+        Technique::NakedSingle | Technique::HiddenSingle => Difficulty::Easy,
+        Technique::LockedCandidates => Difficulty::Medium,
+        _ => Difficulty::Hard,
+    }
+}
 
 pub struct PuzzleGenerator {
     rng: rand::rngs::StdRng,
@@ -24,6 +156,12 @@ impl PuzzleGenerator {
     }
 
     pub fn generate_puzzle(&mut self, target_clues: usize) -> Board {
+        self.generate_puzzle_with_progress(target_clues, |_| {})
+    }
+
+    /// `generate_puzzle`, calling `on_progress` with the clue count after every successful removal so a
+    /// front end can show how far a slow, low-clue generation has got.
+    pub fn generate_puzzle_with_progress<F: FnMut(usize)>(&mut self, target_clues: usize, mut on_progress: F) -> Board {
         let mut b = self.generate_full_grid();
         // positions 0..80
         let mut positions: Vec<usize> = (0..81).collect();
@@ -45,6 +183,7 @@ impl PuzzleGenerator {
                 b.cells[r][c].value = old;
             } else {
                 clues -= 1;
+                on_progress(clues);
             }
         }
         // finalize fixed flags
@@ -52,8 +191,89 @@ impl PuzzleGenerator {
         b
     }
 
+    /// Like `generate_puzzle`, but clues are removed in symmetric pairs so the givens keep `sym`.
+    /// A pair is only removed if the puzzle stays unique without both cells, and never if that would take the
+    /// count below `target_clues`, so an odd gap may leave one clue more than asked for.
+    pub fn generate_symmetric_puzzle(&mut self, target_clues: usize, sym: Symmetry) -> Board {
+        let mut b = self.generate_full_grid();
+        let mut positions: Vec<usize> = (0..81).collect();
+        positions.shuffle(&mut self.rng);
+        let mut clues = 81usize;
+        for idx in positions {
+            if clues <= target_clues { break; }
+            let (r, c) = (idx / 9, idx % 9);
+            let (pr, pc) = sym.partner(r, c);
+            if b.cells[r][c].value == 0 { continue; }
+            let removed = if (pr, pc) == (r, c) { 1 } else { 2 };
+            if clues - removed < target_clues { continue; }
+            let old = b.cells[r][c].value; let old_p = b.cells[pr][pc].value;
+            b.cells[r][c].value = 0; b.cells[pr][pc].value = 0;
+            b.cells[r][c].fixed = false; b.cells[pr][pc].fixed = false;
+            let mut copy = b.clone();
+            if count_solutions(&mut copy, 2) != 1 {
+                b.cells[r][c].value = old; b.cells[pr][pc].value = old_p;
+            } else {
+                clues -= removed;
+            }
+        }
+        for r in 0..9 { for c in 0..9 { let v = b.cells[r][c].value; b.cells[r][c].fixed = v != 0; }}
+        b
+    }
+
+    /// Remove every clue of `board` that isn't needed for a unique solution, in random order,
+    /// until a full pass removes nothing. Boards that aren't unique to begin with come back unchanged.
+    pub fn minimize(&mut self, board: &Board) -> Board {
+        let mut b = board.clone();
+        for r in 0..9 { for c in 0..9 { b.cells[r][c].fixed = false; }}
+        if count_solutions_board(&b, 2) != 1 { return board.clone(); }
+        loop {
+            let mut positions: Vec<usize> = (0..81).filter(|&i| b.cells[i / 9][i % 9].value != 0).collect();
+            positions.shuffle(&mut self.rng);
+            let mut removed = false;
+            for idx in positions {
+                let (r, c) = (idx / 9, idx % 9);
+                let old = b.cells[r][c].value;
+                b.cells[r][c].value = 0;
+                let mut copy = b.clone();
+                if count_solutions(&mut copy, 2) == 1 { removed = true; } else { b.cells[r][c].value = old; }
+            }
+            if !removed { break; }
+        }
+        for r in 0..9 { for c in 0..9 { let v = b.cells[r][c].value; b.cells[r][c].fixed = v != 0; }}
+        b
+    }
+
+    /// Generate a puzzle like `generate_puzzle` and rate how hard it is to solve.
+    pub fn generate_rated_puzzle(&mut self, target_clues: usize) -> (Board, Difficulty) {
+        let b = self.generate_puzzle(target_clues);
+        let d = rate_difficulty(&b);
+        (b, d)
+    }
+
+    /// Generate puzzles until one rates at most `max_difficulty`, so e.g. an Easy request never needs more
+    /// than singles. Gives up after a fixed number of attempts and returns the last puzzle generated.
+    pub fn generate_logical_puzzle(&mut self, target_clues: usize, max_difficulty: Difficulty) -> Board {
+        const MAX_ATTEMPTS: usize = 50;
+        let mut last = Board::empty();
+        for _ in 0..MAX_ATTEMPTS {
+            let (b, d) = self.generate_rated_puzzle(target_clues);
+            if d <= max_difficulty { return b; }
+            last = b;
+        }
+        last
+    }
+
+    /// A unique puzzle with exactly `clues` givens. `generate_puzzle` can stop above its target when every
+    /// further removal would break uniqueness, so this retries with fresh grids and removal orders and gives
+    /// up with `None` after a fixed number of attempts. Below 17 clues no unique puzzle exists at all.
+    pub fn generate_exact_clues(&mut self, clues: usize) -> Option<Board> {
+        const MAX_ATTEMPTS: usize = 50;
+        if !(17..=81).contains(&clues) { return None; }
+        (0..MAX_ATTEMPTS).map(|_| self.generate_puzzle(clues)).find(|b| b.filled_count() == clues)
+    }
+
     fn fill_grid(&mut self, b: &mut Board) -> bool {
-        if let Some((r, c)) = find_next_mrv(b) {
+        if let Some((r, c)) = mrv(b) {
             let mut digits: Vec<u8> = (1..=9).collect();
             digits.shuffle(&mut self.rng);
             for d in digits {
@@ -70,21 +290,23 @@ impl PuzzleGenerator {
     }
 }
 
-fn find_next_mrv(b: &Board) -> Option<(usize, usize)> {
-    let mut best: Option<(usize, usize, usize)> = None; // (r,c,count)
-    for r in 0..9 { for c in 0..9 { if b.cells[r][c].value == 0 {
-        let cand = b.candidates(r, c);
-        let mut cnt = 0; for v in 1..=9 { if cand[v as usize] { cnt += 1; } }
-        if cnt == 0 { return Some((r, c)); }
-        match best { None => best = Some((r,c,cnt)), Some((_,_,bc)) if cnt < bc => best = Some((r,c,cnt)), _ => {} }
-    }}}
-    best.map(|(r,c,_)| (r,c))
+/// The full grid a generator seeded with `seed` starts from; the same seed always gives the same grid.
+pub fn generate_full_grid_seeded(seed: u64) -> Board { PuzzleGenerator::new(Some(seed)).generate_full_grid() }
+
+/// Count the solutions of `board`, stopping once `limit` is reached.
+/// 0 means the givens are contradictory, 1 means the puzzle is unique.
+pub fn count_solutions_board(board: &Board, limit: usize) -> usize {
+    // Conflicting givens can never complete; don't search the whole tree to find that out
+    if !board.is_valid() { return 0; }
+    let mut copy = board.clone();
+    count_solutions(&mut copy, limit)
 }
 
 fn count_solutions(b: &mut Board, limit: usize) -> usize {
     fn backtrack(b: &mut Board, count: &mut usize, limit: usize) {
         if *count >= limit { return; }
-        if let Some((r,c)) = find_empty(b) {
+        // MRV keeps sparse boards (minimization, uniqueness checks) from blowing up
+        if let Some((r,c)) = mrv(b) {
             let cand = b.candidates(r,c);
             for d in 1..=9u8 {
                 if cand[d as usize] {
@@ -99,7 +321,8 @@ fn count_solutions(b: &mut Board, limit: usize) -> usize {
             if b.is_valid() { *count += 1; }
         }
     }
-    fn find_empty(b: &Board) -> Option<(usize,usize)> { for r in 0..9 { for c in 0..9 { if b.cells[r][c].value == 0 { return Some((r,c)); } }} None }
+    // The search writes `cells` directly, so a cache copied in with the board would go stale
+    b.invalidate_candidates();
     let mut count = 0;
     backtrack(b, &mut count, limit);
     count
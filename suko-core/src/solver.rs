@@ -1,11 +1,15 @@
-use crate::board::Board;
+use crate::board::{Board, Unit};
 use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum StepKind {
-    Place { r: usize, c: usize, v: u8, reason: String },
+    /// `technique` is the one `reason` names, for ratings that shouldn't depend on the wording
+    Place { r: usize, c: usize, v: u8, reason: String, #[serde(default)] technique: Technique },
     Guess { r: usize, c: usize, v: u8 },
     Backtrack,
+    /// A candidate ruled out on the way to a placement; the board is unchanged
+    Eliminate { r: usize, c: usize, v: u8, reason: String },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,8 +22,49 @@ pub struct Step {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SolverOutcome { Solved, Unsolvable, Incomplete }
 
+/// Logical techniques, easiest first. A placement after several reductions carries the first fish, wing,
+/// coloring or uniqueness pattern among them, or else the hardest basic reduction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Technique {
+    /// Also what steps saved before techniques were recorded read back as
+    #[default]
+    NakedSingle,
+    HiddenSingle,
+    /// Pointing and claiming
+    LockedCandidates,
+    NakedPair,
+    HiddenPair,
+    NakedTriple,
+    HiddenTriple,
+    NakedQuad,
+    XWing,
+    Swordfish,
+    XyWing,
+    WWing,
+    Jellyfish,
+    SimpleColoring,
+    RemotePair,
+    UniqueRectangle,
+}
+
+impl Display for Technique {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Technique::NakedSingle => "Naked single", Technique::HiddenSingle => "Hidden single",
+            Technique::LockedCandidates => "Locked candidates", Technique::NakedPair => "Naked pair",
+            Technique::HiddenPair => "Hidden pair", Technique::NakedTriple => "Naked triple",
+            Technique::HiddenTriple => "Hidden triple", Technique::NakedQuad => "Naked quad",
+            Technique::XWing => "X-Wing", Technique::Swordfish => "Swordfish", Technique::XyWing => "XY-Wing",
+            Technique::WWing => "W-Wing", Technique::Jellyfish => "Jellyfish", Technique::SimpleColoring => "Simple coloring",
+            Technique::RemotePair => "Remote pair", Technique::UniqueRectangle => "Unique rectangle",
+        };
+        write!(f, "{}", s)
+    }
+}
+
 pub trait Solver {
     fn name(&self) -> &str;
+    /// Eliminations leading up to a placement don't count toward `max_steps`.
     fn solve_steps(&mut self, board: &Board, max_steps: Option<usize>) -> Vec<Step>;
 }
 
@@ -29,6 +74,7 @@ pub trait Solver {
 /// - If a value conflicts, decrease to the next lower value
 /// - If all values 9..1 conflict for the current cell, backtrack to the previous empty cell and decrease it
 /// - Continue until all cells are filled or no solution exists
+#[derive(Default)]
 pub struct BacktracingBruteSolver;
 impl BacktracingBruteSolver {
     pub fn new() -> Self { Self }
@@ -75,94 +121,319 @@ impl BacktracingBruteSolver {
         // i == empties.len() => all placed
         if b.is_valid() { Some(b) } else { None }
     }
+
+    /// Like `solve_to_completion`, but rejects a grid whose givens already conflict instead of searching it.
+    pub fn solve_to_completion_checked(&mut self, board: &Board) -> Result<Option<Board>, String> {
+        if !board.is_valid() { return Err("input grid already contains conflicts".into()); }
+        Ok(self.solve_to_completion(board))
+    }
+
+    /// Like `solve_to_completion`, but fills the cell with the fewest candidates next (see `mrv`) instead of
+    /// scanning row-major, so puzzles built against the fixed order don't blow up. Returns None for a
+    /// conflicting or unsolvable grid.
+    pub fn solve_to_completion_mrv(&self, board: &Board) -> Option<Board> {
+        if !board.is_valid() { return None; }
+        let mut b = board.clone();
+        let mut assignments = 0;
+        fill_mrv(&mut b, &mut assignments).then_some(b)
+    }
 }
 
-pub struct BacktrackingSolver;
-impl BacktrackingSolver {
-    pub fn new() -> Self { Self }
+// Depth-first fill by MRV, counting every value written into `assignments`
+fn fill_mrv(b: &mut Board, assignments: &mut usize) -> bool {
+    let Some((r, c)) = mrv(b) else { return true };
+    let cand = b.candidates(r, c);
+    for v in 1..=9u8 {
+        if !cand[v as usize] { continue; }
+        *assignments += 1;
+        b.set_value(r, c, v);
+        if fill_mrv(b, assignments) { return true; }
+    }
+    b.set_value(r, c, 0);
+    false
 }
 
-impl Solver for BacktrackingSolver {
-    fn name(&self) -> &str { "Backtracking" }
-    fn solve_steps(&mut self, board: &Board, max_steps: Option<usize>) -> Vec<Step> {
+/// Search effort counted by `BacktrackingSolver::solve_stats`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    pub solved: bool,
+    /// Values tried (one per guess)
+    pub nodes: usize,
+    pub backtracks: usize,
+    /// Most guesses stacked up at once
+    pub max_depth: usize,
+}
+
+#[derive(Default)]
+pub struct BacktrackingSolver {
+    // Try the least constraining value first instead of 1..9
+    lcv: bool,
+}
+impl BacktrackingSolver {
+    pub fn new() -> Self { Self::default() }
+
+    /// With `lcv` set, each guessed cell tries first the digits that rule out the fewest peer candidates.
+    pub fn with_lcv(lcv: bool) -> Self { Self { lcv } }
+
+    /// Like `solve_steps`, but also reports whether the steps end in a solution, a dead end,
+    /// or were cut short by `max_steps`.
+    pub fn solve_with_outcome(&mut self, board: &Board, max_steps: Option<usize>) -> (Vec<Step>, SolverOutcome) {
         let mut steps = Vec::new();
+        if !board.is_valid() { return (steps, SolverOutcome::Unsolvable); }
         let mut b = board.clone();
-        // Minimum Remaining Values (MRV): pick the empty cell with the fewest candidates (>0). If any empty cell has 0 candidates, fail fast.
-        fn find_mrv(b: &Board) -> Option<(usize,usize,[bool;10], usize)> {
-            let mut best: Option<(usize,usize,[bool;10], usize)> = None;
-            for r in 0..9 { for c in 0..9 {
-                if b.cells[r][c].value==0 {
-                    let cand = b.candidates(r,c);
-                    let count = (1..=9).filter(|&v| cand[v as usize]).count();
-                    if count==0 { return Some((r,c,cand,0)); }
-                    match best {
-                        None => best = Some((r,c,cand,count)),
-                        Some((_,_,_,bc)) if count < bc => best = Some((r,c,cand,count)),
-                        _ => {}
-                    }
-                }
-            }}
-            best
-        }
-        fn any_zero_candidate(b: &Board) -> bool {
-            for r in 0..9 { for c in 0..9 { if b.cells[r][c].value==0 {
-                let cand=b.candidates(r,c);
-                if (1..=9).all(|v| !cand[v as usize]) { return true; }
-            }}}
-            false
-        }
-        fn rec(b: &mut Board, steps: &mut Vec<Step>, idx: &mut usize, max: Option<usize>) -> bool {
+        // The search reads candidates constantly, so keep them cached and update through set_value
+        b.recompute_candidates();
+        fn rec(b: &mut Board, lcv: bool, steps: &mut Vec<Step>, idx: &mut usize, max: Option<usize>, truncated: &mut bool) -> bool {
             if b.is_solved() { return true; }
-            if let Some(m)=max { if *idx >= m { return false; } }
-            let Some((r,c,cand,_cnt)) = find_mrv(b) else { return true; };
+            if let Some(m)=max { if *idx >= m { *truncated = true; return false; } }
+            let Some((r,c)) = mrv(b) else { return false; };
+            let cand = b.candidates(r, c);
             if (1..=9).all(|v| !cand[v as usize]) { return false; }
-            for v in 1..=9 {
-                if !cand[v as usize] { continue; }
-                b.cells[r][c].value = v;
+            for v in value_order(b, r, c, &cand, lcv) {
+                b.set_value(r, c, v);
                 *idx += 1;
-                steps.push(Step{ index:*idx, kind: StepKind::Guess{ r, c, v }, board: b.clone() });
-                if b.is_valid() && !any_zero_candidate(b) && rec(b, steps, idx, max) { return true; }
+                steps.push(Step{ index:*idx, kind: StepKind::Guess{ r, c, v }, board: snapshot(b) });
+                if b.is_valid() && !any_zero_candidate(b) && rec(b, lcv, steps, idx, max, truncated) { return true; }
+                if *truncated { return false; }
                 // backtrack
-                b.cells[r][c].value = 0;
-                *idx += 1; steps.push(Step{ index:*idx, kind: StepKind::Backtrack, board: b.clone() });
-                if let Some(m)=max { if *idx >= m { return false; } }
+                b.set_value(r, c, 0);
+                *idx += 1; steps.push(Step{ index:*idx, kind: StepKind::Backtrack, board: snapshot(b) });
+                if let Some(m)=max { if *idx >= m { *truncated = true; return false; } }
+            }
+            false
+        }
+        let mut idx=0usize; let mut truncated=false;
+        let outcome = if rec(&mut b, self.lcv, &mut steps, &mut idx, max_steps, &mut truncated) { SolverOutcome::Solved }
+            else if truncated { SolverOutcome::Incomplete }
+            else { SolverOutcome::Unsolvable };
+        (steps, outcome)
+    }
+
+    /// Run the same search as `solve_with_outcome` but only count the work, without building steps.
+    pub fn solve_stats(&mut self, board: &Board) -> SolveStats {
+        let mut stats = SolveStats::default();
+        if !board.is_valid() { return stats; }
+        let mut b = board.clone();
+        b.recompute_candidates();
+        fn rec(b: &mut Board, lcv: bool, stats: &mut SolveStats, depth: usize) -> bool {
+            if b.is_solved() { return true; }
+            let Some((r,c)) = mrv(b) else { return false; };
+            let cand = b.candidates(r, c);
+            for v in value_order(b, r, c, &cand, lcv) {
+                b.set_value(r, c, v);
+                stats.nodes += 1;
+                stats.max_depth = stats.max_depth.max(depth + 1);
+                if b.is_valid() && !any_zero_candidate(b) && rec(b, lcv, stats, depth + 1) { return true; }
+                b.set_value(r, c, 0);
+                stats.backtracks += 1;
             }
             false
         }
-        let mut idx=0usize; let solved = rec(&mut b, &mut steps, &mut idx, max_steps);
-        if solved { steps } else { steps }
+        stats.solved = rec(&mut b, self.lcv, &mut stats, 0);
+        stats
     }
 }
 
-pub struct LogicalSolver;
-impl LogicalSolver { pub fn new() -> Self { Self } }
+/// Minimum Remaining Values: the empty cell with the fewest candidates, or `None` if the board is full.
+/// Ties go to the lowest row-major index, and a cell with no candidates at all is returned at once so the
+/// caller fails fast. Every search in the crate picks its next cell through this, so runs are reproducible.
+pub fn mrv(b: &Board) -> Option<(usize, usize)> {
+    let mut best: Option<(usize, usize, u32)> = None;
+    for r in 0..9 { for c in 0..9 { if b.cells[r][c].value == 0 {
+        let cand = b.candidates(r, c);
+        let count = (1..=9).filter(|&v| cand[v]).count() as u32;
+        if count == 0 { return Some((r, c)); }
+        // Strictly fewer only, so the first cell scanned wins a tie
+        if best.is_none_or(|(_, _, bc)| count < bc) { best = Some((r, c, count)); }
+    }}}
+    best.map(|(r, c, _)| (r, c))
+}
 
-impl Solver for LogicalSolver {
-    fn name(&self) -> &str { "Logical" }
+// Candidate digits of (r,c) in the order to try them: ascending, or with `lcv` by how many empty peers
+// would lose that digit as a candidate (fewest first, ties ascending)
+fn value_order(b: &Board, r: usize, c: usize, cand: &[bool;10], lcv: bool) -> Vec<u8> {
+    let mut vals: Vec<u8> = (1..=9).filter(|&v| cand[v as usize]).collect();
+    if lcv {
+        vals.sort_by_key(|&v| {
+            let mut n = 0;
+            for pr in 0..9 { for pc in 0..9 {
+                if (pr, pc) == (r, c) || b.cells[pr][pc].value != 0 { continue; }
+                let peer = pr == r || pc == c || (pr/3 == r/3 && pc/3 == c/3);
+                if peer && b.candidates(pr, pc)[v as usize] { n += 1; }
+            }}
+            n
+        });
+    }
+    vals
+}
+
+fn any_zero_candidate(b: &Board) -> bool {
+    for r in 0..9 { for c in 0..9 { if b.cells[r][c].value==0 {
+        let cand=b.candidates(r,c);
+        if (1..=9).all(|v| !cand[v as usize]) { return true; }
+    }}}
+    false
+}
+
+impl Solver for BacktrackingSolver {
+    fn name(&self) -> &str { "Backtracking" }
     fn solve_steps(&mut self, board: &Board, max_steps: Option<usize>) -> Vec<Step> {
+        self.solve_with_outcome(board, max_steps).0
+    }
+}
+
+/// How far `LogicalSolver::solve_report` got: the grid where logic stopped, how many cells are still empty,
+/// and the reason of the last placement it made.
+#[derive(Debug, Clone)]
+pub struct LogicReport {
+    pub board: Board,
+    pub empty: usize,
+    pub last_reason: Option<String>,
+}
+
+impl LogicReport {
+    /// e.g. "Logic solved 64/81 cells; stuck after Hidden single in row 3".
+    pub fn summary(&self) -> String {
+        let filled = 81 - self.empty;
+        if self.empty == 0 { return "Logic solved all 81 cells".to_string(); }
+        match &self.last_reason {
+            Some(reason) => format!("Logic solved {}/81 cells; stuck after {}", filled, reason),
+            None => format!("Logic solved {}/81 cells; no logical step applies", filled),
+        }
+    }
+}
+
+/// Techniques the logical solver can be limited to, see `LogicalSolver::with_strategies`. Unique rectangles
+/// are switched on by `LogicalSolver::with_assume_unique` instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    NakedSingle,
+    HiddenSingle,
+    /// Pointing and claiming
+    LockedCandidates,
+    NakedPair,
+    HiddenPair,
+    NakedTriple,
+    HiddenTriple,
+    NakedQuad,
+    XWing,
+    Swordfish,
+    Jellyfish,
+    XyWing,
+    WWing,
+    SimpleColoring,
+    RemotePair,
+}
+
+impl Strategy {
+    /// Every strategy, in the order the solver uses by default.
+    pub const ALL: [Strategy; 15] = [
+        Strategy::NakedSingle, Strategy::HiddenSingle,
+        Strategy::LockedCandidates, Strategy::NakedPair, Strategy::HiddenPair, Strategy::NakedTriple, Strategy::HiddenTriple, Strategy::NakedQuad,
+        Strategy::XWing, Strategy::Swordfish, Strategy::Jellyfish,
+        Strategy::XyWing, Strategy::WWing,
+        Strategy::SimpleColoring, Strategy::RemotePair,
+    ];
+
+    // Works by narrowing one cell's candidates down to a single, rather than spotting a placement directly
+    fn is_reduction(self) -> bool { !matches!(self, Strategy::NakedSingle | Strategy::HiddenSingle) }
+
+    // The technique its steps are tagged with
+    fn technique(self) -> Technique {
+        match self {
+            Strategy::NakedSingle => Technique::NakedSingle,
+            Strategy::HiddenSingle => Technique::HiddenSingle,
+            Strategy::LockedCandidates => Technique::LockedCandidates,
+            Strategy::NakedPair => Technique::NakedPair,
+            Strategy::HiddenPair => Technique::HiddenPair,
+            Strategy::NakedTriple => Technique::NakedTriple,
+            Strategy::HiddenTriple => Technique::HiddenTriple,
+            Strategy::NakedQuad => Technique::NakedQuad,
+            Strategy::XWing => Technique::XWing,
+            Strategy::Swordfish => Technique::Swordfish,
+            Strategy::Jellyfish => Technique::Jellyfish,
+            Strategy::XyWing => Technique::XyWing,
+            Strategy::WWing => Technique::WWing,
+            Strategy::SimpleColoring => Technique::SimpleColoring,
+            Strategy::RemotePair => Technique::RemotePair,
+        }
+    }
+}
+
+pub struct LogicalSolver {
+    // Allow techniques that are only sound when the puzzle has exactly one solution
+    assume_unique: bool,
+    strategies: Vec<Strategy>,
+}
+impl Default for LogicalSolver {
+    fn default() -> Self { Self { assume_unique: false, strategies: Strategy::ALL.to_vec() } }
+}
+impl LogicalSolver {
+    pub fn new() -> Self { Self::default() }
+
+    /// With `assume_unique` set, uniqueness-based techniques (unique rectangles) are tried as well. Their
+    /// eliminations can be wrong on a puzzle with several solutions.
+    pub fn with_assume_unique(assume_unique: bool) -> Self { Self { assume_unique, ..Self::default() } }
+
+    /// Only use the listed strategies. The singles are tried in the given order; the reduction strategies
+    /// (everything from `LockedCandidates` on) work together as one pass, tried where the first of them is
+    /// listed, in the pass's own order: subsets, fish, wings, then coloring. An empty list makes no progress at all. Every technique is sound on its own, so any order
+    /// or subset only places correct digits; it may just get stuck sooner.
+    pub fn with_strategies(strategies: Vec<Strategy>) -> Self { Self { strategies, ..Self::default() } }
+
+    /// Like `solve_steps`, but calls `on_step` with each step as soon as it is found.
+    pub fn solve_steps_with<F: FnMut(&Step)>(&mut self, board: &Board, max_steps: Option<usize>, on_step: F) -> Vec<Step> {
+        self.run(board.clone(), max_steps, on_step)
+    }
+
+    /// Like `solve_steps`, but each cell's computed candidates are intersected with the user's pencil marks
+    /// (bit v set for digit v; 0 leaves a cell unrestricted). Marks that rule out the true digit can leave a
+    /// cell with no candidates, in which case the solver may report no progress or stop early.
+    pub fn solve_steps_with_marks(&mut self, board: &Board, marks: &[[u16;9];9], max_steps: Option<usize>) -> Vec<Step> {
         let mut b = board.clone();
+        b.restrict_candidates(marks);
+        self.run(b, max_steps, |_| {})
+    }
+
+    /// Solve as far as logic goes and report where it stopped.
+    pub fn solve_report(&mut self, board: &Board) -> LogicReport {
+        let steps = self.run(board.clone(), None, |_| {});
+        let last_reason = steps.iter().rev().find_map(|s| match &s.kind { StepKind::Place{ reason, .. } => Some(reason.clone()), _ => None });
+        let board = steps.last().map_or_else(|| board.clone(), |s| s.board.clone());
+        let empty = 81 - board.filled_count();
+        LogicReport { board, empty, last_reason }
+    }
+
+    fn run<F: FnMut(&Step)>(&mut self, mut b: Board, max_steps: Option<usize>, mut on_step: F) -> Vec<Step> {
         let mut steps = Vec::new();
         let mut idx=0usize;
+        let mut placed=0usize;
+        let mut push = |step: Step| { on_step(&step); steps.push(step); };
         // produce at most one logical step unless max_steps allows more
         while !b.is_solved() {
-            if let Some(m)=max_steps { if idx>=m { break; } }
-            // Strategy priority:
-            // 1) Naked singles
-            if let Some((r,c,v,reason)) = find_naked_single(&b) {
-                apply_place(&mut b, r, c, v);
-                idx+=1; steps.push(Step{ index: idx, kind: StepKind::Place{ r,c,v,reason }, board: b.clone() });
-                continue;
+            if let Some(m)=max_steps { if placed>=m { break; } }
+            placed+=1;
+            // Strategies in the configured order, the first one that places a digit wins
+            let reductions_at = self.strategies.iter().position(|s| s.is_reduction());
+            let mut found = None;
+            for (i, &strategy) in self.strategies.iter().enumerate() {
+                found = match strategy {
+                    Strategy::NakedSingle => find_naked_single(&b).map(|(r,c,v,reason)| (r,c,v,reason,Technique::NakedSingle,Vec::new())),
+                    Strategy::HiddenSingle => find_hidden_single(&b).map(|(r,c,v,reason)| (r,c,v,reason,Technique::HiddenSingle,Vec::new())),
+                    // Reductions (locked candidates pointing/claiming, naked/hidden pairs, triples, quads, fish, XY-Wing,
+                    // W-Wing, coloring, remote pairs, and unique rectangles when assuming uniqueness) leading to a single
+                    _ if Some(i) == reductions_at => find_single_after_reductions(&b, &self.strategies, self.assume_unique),
+                    _ => None,
+                };
+                if found.is_some() { break; }
             }
-            // 2) Hidden singles
-            if let Some((r,c,v,reason)) = find_hidden_single(&b) {
-                apply_place(&mut b, r, c, v);
-                idx+=1; steps.push(Step{ index: idx, kind: StepKind::Place{ r,c,v,reason }, board: b.clone() });
-                continue;
-            }
-            // 3) Reductions (locked candidates pointing/claiming, naked pairs) leading to a single
-            if let Some((r,c,v,reason)) = find_single_after_reductions(&b) {
+            if let Some((r,c,v,reason,technique,elims)) = found {
+                // Record what each reduction ruled out before the placement it enables
+                for (ev, why) in elims {
+                    idx+=1; push(Step{ index: idx, kind: StepKind::Eliminate{ r,c,v: ev,reason: why }, board: snapshot(&b) });
+                }
                 apply_place(&mut b, r, c, v);
-                idx+=1; steps.push(Step{ index: idx, kind: StepKind::Place{ r,c,v,reason }, board: b.clone() });
+                idx+=1; push(Step{ index: idx, kind: StepKind::Place{ r,c,v,reason,technique }, board: snapshot(&b) });
                 continue;
             }
             break;
@@ -171,7 +442,36 @@ impl Solver for LogicalSolver {
     }
 }
 
-fn apply_place(b: &mut Board, r: usize, c: usize, v: u8) { b.cells[r][c].value = v; }
+impl Solver for LogicalSolver {
+    fn name(&self) -> &str { "Logical" }
+    fn solve_steps(&mut self, board: &Board, max_steps: Option<usize>) -> Vec<Step> {
+        self.solve_steps_with(board, max_steps, |_| {})
+    }
+}
+
+/// Solve `board` outright: logic first, then backtracking from wherever logic got stuck.
+/// Returns None if the grid has no solution.
+///
+/// ```
+/// use suko_core::{board::Board, solver::solve};
+/// let puzzle = Board::parse("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79").unwrap();
+/// let solved = solve(&puzzle).unwrap();
+/// assert!(solved.is_solved());
+/// assert_eq!(solved.cells[0][2].value, 4);
+/// ```
+pub fn solve(board: &Board) -> Option<Board> {
+    if !board.is_valid() { return None; }
+    let after_logic = LogicalSolver::new().solve_steps(board, None).pop().map(|s| s.board).unwrap_or_else(|| board.clone());
+    if after_logic.is_solved() { return Some(after_logic); }
+    // Only the grid is wanted here, so skip the step recording of BacktrackingSolver
+    BacktracingBruteSolver::new().solve_to_completion_mrv(&after_logic)
+}
+
+// Goes through set_value so a restricted candidate cache (see solve_steps_with_marks) stays in sync
+fn apply_place(b: &mut Board, r: usize, c: usize, v: u8) { b.set_value(r, c, v); }
+
+// Boards handed out in steps drop the cache, since callers edit `cells` directly
+fn snapshot(b: &Board) -> Board { let mut s = b.clone(); s.invalidate_candidates(); s }
 
 fn find_naked_single(b: &Board) -> Option<(usize,usize,u8,String)> {
     for r in 0..9 { for c in 0..9 { if b.cells[r][c].value==0 {
@@ -204,22 +504,85 @@ fn find_hidden_single(b: &Board) -> Option<(usize,usize,u8,String)> {
     None
 }
 
-fn find_single_after_reductions(b: &Board) -> Option<(usize,usize,u8,String)> {
+// A per-cell reduction: given the board and the current candidate mask of (r,c), return the narrowed mask
+type Reduction = fn(&Board, usize, usize, u16) -> u16;
+
+// Basic per-cell reductions in the order they are tried, with the name used in step reasons
+const BASIC_REDUCTIONS: [(&str, Technique, Reduction); 6] = [
+    ("pointing/claiming", Technique::LockedCandidates, apply_locked_pointing_claiming),
+    ("pairs", Technique::NakedPair, apply_naked_pairs_all_units),
+    ("hidden pairs", Technique::HiddenPair, apply_hidden_pairs_all_units),
+    ("naked triples", Technique::NakedTriple, apply_naked_triples_all_units),
+    ("naked quads", Technique::NakedQuad, apply_naked_quads_all_units),
+    ("hidden triples", Technique::HiddenTriple, apply_hidden_triples_all_units),
+];
+
+// Candidates ruled out in a cell, each with the technique that did it
+type Eliminations = Vec<(u8,String)>;
+
+// A placement found by the reductions: cell, digit, reason, technique, and the eliminations that led to it
+type Placement = (usize,usize,u8,String,Technique,Eliminations);
+
+// Returns the placement plus the eliminations in that cell that led to it. Simple coloring is only tried once
+// no cell yields to the other techniques, so the cheaper patterns keep priority anywhere on the board.
+fn find_single_after_reductions(b: &Board, strategies: &[Strategy], unique: bool) -> Option<Placement> {
+    let coloring = strategies.iter().any(|s| matches!(s, Strategy::SimpleColoring | Strategy::RemotePair));
+    find_single_with(b, false, strategies, unique).or_else(|| if coloring { find_single_with(b, true, strategies, unique) } else { None })
+}
+
+fn find_single_with(b: &Board, coloring: bool, strategies: &[Strategy], unique: bool) -> Option<Placement> {
+    let on = |t: Technique| strategies.iter().any(|s| s.technique() == t);
     // Try to derive a single for any cell by applying human-style reductions
     for r in 0..9 { for c in 0..9 { if b.cells[r][c].value==0 {
         let mut mask = mask_from_candidates(b.candidates(r,c));
         if mask.count_ones() <= 1 { continue; }
         // Iterate reductions until stable (at most 9 bits)
+        // Fish and wing patterns are only tried once the basic reductions alone leave more than one candidate.
+        let mut fish: Option<(String, Technique)> = None;
+        let mut used = [false; BASIC_REDUCTIONS.len()];
+        let mut elims: Eliminations = Vec::new();
+        let mut note = |from: u16, to: u16, why: &str| for v in 1..=9u8 { if from & !to & (1<<v) != 0 { elims.push((v, why.to_string())); } };
         loop {
             let before = mask;
-            mask = apply_locked_pointing_claiming(b, r, c, mask);
-            mask = apply_naked_pairs_all_units(b, r, c, mask);
+            for (i, &(name, technique, reduce)) in BASIC_REDUCTIONS.iter().enumerate() {
+                if mask.count_ones() <= 1 { break; }
+                if !on(technique) { continue; }
+                let m = reduce(b, r, c, mask);
+                if m != mask { used[i] = true; note(mask, m, name); mask = m; }
+            }
+            for (n, technique) in [(2, Technique::XWing), (3, Technique::Swordfish), (4, Technique::Jellyfish)] {
+                if mask.count_ones() <= 1 { break; }
+                if !on(technique) { continue; }
+                if let Some((m, why)) = apply_fish(b, r, c, mask, n) { note(mask, m, &why); mask = m; fish.get_or_insert((why, technique)); }
+            }
+            if coloring && on(Technique::SimpleColoring) && mask.count_ones() > 1 {
+                if let Some((m, why)) = apply_simple_coloring(b, r, c, mask) { note(mask, m, &why); mask = m; fish.get_or_insert((why, Technique::SimpleColoring)); }
+            }
+            if coloring && on(Technique::RemotePair) && mask.count_ones() > 1 {
+                if let Some((m, why)) = apply_remote_pairs(b, r, c, mask) { note(mask, m, &why); mask = m; fish.get_or_insert((why, Technique::RemotePair)); }
+            }
+            if on(Technique::XyWing) && mask.count_ones() > 1 {
+                if let Some((m, why)) = apply_xy_wing(b, r, c, mask) { note(mask, m, &why); mask = m; fish.get_or_insert((why, Technique::XyWing)); }
+            }
+            if on(Technique::WWing) && mask.count_ones() > 1 {
+                if let Some((m, why)) = apply_w_wing(b, r, c, mask) { note(mask, m, &why); mask = m; fish.get_or_insert((why, Technique::WWing)); }
+            }
+            if unique && mask.count_ones() > 1 {
+                if let Some((m, why)) = apply_unique_rectangle(b, r, c, mask) { note(mask, m, &why); mask = m; fish.get_or_insert((why, Technique::UniqueRectangle)); }
+            }
             if mask == before { break; }
             if mask.count_ones() == 1 { break; }
         }
         if mask.count_ones()==1 {
             let v = (1..=9).find(|&v| (mask & (1<<(v as u16)))!=0 ).unwrap();
-            return Some((r,c,v as u8, "Single after reductions (pointing/claiming, pairs)".into()));
+            // Name only the reductions that were needed, so the difficulty rating can tell them apart
+            let (reason, technique) = fish.unwrap_or_else(|| {
+                let used: Vec<&(&str, Technique, Reduction)> = BASIC_REDUCTIONS.iter().zip(used).filter(|(_, u)| *u).map(|(red, _)| red).collect();
+                let names: Vec<&str> = used.iter().map(|(name, _, _)| *name).collect();
+                let hardest = used.iter().map(|(_, t, _)| *t).max().unwrap_or(Technique::LockedCandidates);
+                (format!("Single after reductions ({})", names.join(", ")), hardest)
+            });
+            return Some((r,c,v as u8, reason, technique, elims));
         }
     }}}
     None
@@ -227,6 +590,11 @@ fn find_single_after_reductions(b: &Board) -> Option<(usize,usize,u8,String)> {
 
 fn mask_from_candidates(cand: [bool;10]) -> u16 { let mut m=0u16; for v in 1..=9 { if cand[v as usize] { m |= 1u16<<v; } } m }
 
+// Candidate masks of every cell, 0 for filled ones
+fn candidate_grid(b: &Board) -> [[u16;9];9] {
+    std::array::from_fn(|r| std::array::from_fn(|c| if b.cells[r][c].value==0 { mask_from_candidates(b.candidates(r,c)) } else { 0 }))
+}
+
 fn apply_locked_pointing_claiming(b: &Board, r: usize, c: usize, mut mask: u16) -> u16 {
     // Implement pointing (box -> row/col) and claiming (row/col -> box) to eliminate candidates for (r,c)
     let br = r/3; let bc = c/3;
@@ -287,6 +655,199 @@ fn apply_locked_pointing_claiming(b: &Board, r: usize, c: usize, mut mask: u16)
     mask
 }
 
+// Fish of size n (2 X-Wing, 3 Swordfish, 4 Jellyfish): if in n rows other than r all candidates for v lie within
+// the same n columns (one of them c), those rows claim v in each of the columns, so (r,c) cannot be v. Same for
+// the column dual. Base lines may hold fewer than n positions as long as together they span exactly n cross-lines.
+fn apply_fish(b: &Board, r: usize, c: usize, mask: u16, n: u32) -> Option<(u16, String)> {
+    let name = match n { 2 => "X-Wing", 3 => "Swordfish", _ => "Jellyfish" };
+    let cands = candidate_grid(b);
+    for v in 1..=9u8 {
+        let bit = 1u16<<v;
+        if mask & bit == 0 { continue; }
+        // row_pos[i]: columns of row i where v is a candidate; col_pos[j]: rows of column j
+        let row_pos: [u16;9] = std::array::from_fn(|rr| (0..9).filter(|&cc| cands[rr][cc] & bit != 0).fold(0, |m, cc| m | 1<<cc));
+        let col_pos: [u16;9] = std::array::from_fn(|cc| (0..9).filter(|&rr| cands[rr][cc] & bit != 0).fold(0, |m, rr| m | 1<<rr));
+        for (pos, line, cross, kind) in [(&row_pos, r, c, "rows"), (&col_pos, c, r, "cols")] {
+            if let Some(base) = find_fish_base(pos, line, cross, n) {
+                let lines: Vec<String> = (0..9).filter(|i| base & (1<<i) != 0).map(|i| (i+1).to_string()).collect();
+                return Some((mask & !bit, format!("{} on {} ({} {})", name, v, kind, lines.join(","))));
+            }
+        }
+    }
+    None
+}
+
+// Look for n base lines (excluding `line`) whose positions together cover exactly n cross-lines, including `cross`.
+// Returns the base lines as a bit set.
+fn find_fish_base(pos: &[u16;9], line: usize, cross: usize, n: u32) -> Option<u16> {
+    let usable = |i: usize| i!=line && (1..=n).contains(&pos[i].count_ones());
+    for set in 0u16..1<<9 {
+        if set.count_ones() != n || (0..9).any(|i| set & (1<<i) != 0 && !usable(i)) { continue; }
+        let cover = (0..9).filter(|i| set & (1<<i) != 0).fold(0u16, |acc, i| acc | pos[i]);
+        if cover.count_ones()==n && cover & (1<<cross) != 0 { return Some(set); }
+    }
+    None
+}
+
+// Simple coloring: for one digit, cells joined by conjugate pairs (units where the digit has exactly two places)
+// alternate between true and false, so each chain splits into two colors. A color that appears twice in one unit
+// is false everywhere (wrap); a cell that sees both colors of a chain cannot hold the digit (trap).
+fn apply_simple_coloring(b: &Board, r: usize, c: usize, mask: u16) -> Option<(u16, String)> {
+    for v in 1..=9u8 {
+        let bit = 1u16<<v;
+        if mask & bit == 0 { continue; }
+        let has = |(rr,cc): (usize,usize)| b.cells[rr][cc].value==0 && b.candidates(rr,cc)[v as usize];
+        let mut links: Vec<((usize,usize),(usize,usize))> = Vec::new();
+        for u in Unit::all() {
+            let ends: Vec<(usize,usize)> = u.positions().into_iter().filter(|&p| has(p)).collect();
+            if ends.len()==2 && !links.contains(&(ends[0], ends[1])) { links.push((ends[0], ends[1])); }
+        }
+        // color[r][c]: (chain index, side) for cells on a chain
+        let mut color: [[Option<(usize,bool)>;9];9] = [[None;9];9];
+        let mut chains = 0usize;
+        for &(start, _) in &links {
+            if color[start.0][start.1].is_some() { continue; }
+            color[start.0][start.1] = Some((chains, false));
+            let mut stack = vec![start];
+            while let Some(p) = stack.pop() {
+                let side = color[p.0][p.1].unwrap().1;
+                for &(a, q) in &links {
+                    let next = if a == p { q } else if q == p { a } else { continue };
+                    if color[next.0][next.1].is_none() { color[next.0][next.1] = Some((chains, !side)); stack.push(next); }
+                }
+            }
+            chains += 1;
+        }
+        for k in 0..chains {
+            let members = |side: bool| -> Vec<(usize,usize)> { (0..81).map(|i| (i/9, i%9)).filter(|&(rr,cc)| color[rr][cc] == Some((k, side))).collect() };
+            let (blue, green) = (members(false), members(true));
+            let names = |cells: &[(usize,usize)]| cells.iter().map(|(rr,cc)| format!("r{}c{}", rr+1, cc+1)).collect::<Vec<_>>().join(",");
+            let why = |kind: &str| format!("Simple coloring on {} ({}: {} / {})", v, kind, names(&blue), names(&green));
+            let target = color[r][c].filter(|&(kk,_)| kk==k);
+            match target {
+                Some((_, side)) => {
+                    let same = if side { &green } else { &blue };
+                    if same.iter().any(|&p| same.iter().any(|&q| sees(p, q))) { return Some((mask & !bit, why("color wrap"))); }
+                }
+                None => {
+                    if blue.iter().any(|&p| sees(p, (r,c))) && green.iter().any(|&p| sees(p, (r,c))) { return Some((mask & !bit, why("color trap"))); }
+                }
+            }
+        }
+    }
+    None
+}
+
+// XY-Wing: a pivot {X,Y} sees two pincers {X,Z} and {Y,Z}. Whichever value the pivot takes, one pincer
+// becomes Z, so any cell seeing both pincers (here (r,c)) cannot be Z.
+fn apply_xy_wing(b: &Board, r: usize, c: usize, mask: u16) -> Option<(u16, String)> {
+    let cands = candidate_grid(b);
+    for z in 1..=9u8 {
+        let zbit = 1u16<<z;
+        if mask & zbit == 0 { continue; }
+        // Bi-value cells holding Z that see the target are the possible pincers
+        let pincers: Vec<(usize,usize)> = b.bivalue_cells().into_iter()
+            .filter(|&(rr,cc,p,q)| (p == z || q == z) && sees((rr,cc), (r,c))).map(|(rr,cc,_,_)| (rr,cc)).collect();
+        for i in 0..pincers.len() { for j in i+1..pincers.len() {
+            let (a, bb) = (pincers[i], pincers[j]);
+            let x = cands[a.0][a.1] & !zbit; let y = cands[bb.0][bb.1] & !zbit;
+            if x == y { continue; }
+            for (pr, row) in cands.iter().enumerate() { for (pc, &m) in row.iter().enumerate() {
+                if m == (x | y) && (pr,pc) != (r,c) && sees((pr,pc), a) && sees((pr,pc), bb) {
+                    return Some((mask & !zbit, format!("XY-Wing (pivot r{}c{}, Z={})", pr+1, pc+1, z)));
+                }
+            }}
+        }}
+    }
+    None
+}
+
+// W-Wing: two unconnected bi-value cells {X,Y} whose Y candidates are joined by a strong link (a unit where Y
+// has exactly two places, one seeing each cell). One of them must then be X, so cells seeing both cannot be X.
+fn apply_w_wing(b: &Board, r: usize, c: usize, mask: u16) -> Option<(u16, String)> {
+    let cands = candidate_grid(b);
+    for x in 1..=9u8 {
+        let xbit = 1u16<<x;
+        if mask & xbit == 0 { continue; }
+        let wings: Vec<(usize,usize)> = b.bivalue_cells().into_iter()
+            .filter(|&(rr,cc,p,q)| (p == x || q == x) && sees((rr,cc), (r,c))).map(|(rr,cc,_,_)| (rr,cc)).collect();
+        for i in 0..wings.len() { for j in i+1..wings.len() {
+            let (a, bb) = (wings[i], wings[j]);
+            if cands[a.0][a.1] != cands[bb.0][bb.1] || sees(a, bb) { continue; }
+            let y = (cands[a.0][a.1] & !xbit).trailing_zeros() as usize;
+            for u in Unit::all() {
+                let ends: Vec<(usize,usize)> = u.positions().into_iter().filter(|&(rr,cc)| cands[rr][cc] & (1<<y) != 0).collect();
+                if ends.len() != 2 || ends.contains(&a) || ends.contains(&bb) { continue; }
+                let (p, q) = (ends[0], ends[1]);
+                if (sees(p, a) && sees(q, bb)) || (sees(p, bb) && sees(q, a)) {
+                    return Some((mask & !xbit, "W-Wing".to_string()));
+                }
+            }
+        }}
+    }
+    None
+}
+
+// Remote pairs: bi-value cells all holding {X,Y}, each seeing the next, alternate X and Y along the chain.
+// Two cells an odd number of links apart (3 or more) are one X and one Y, so a cell seeing both can be neither.
+fn apply_remote_pairs(b: &Board, r: usize, c: usize, mask: u16) -> Option<(u16, String)> {
+    let bivalue: Vec<((usize,usize), u16)> = b.bivalue_cells().into_iter()
+        .filter(|&(rr,cc,_,_)| (rr, cc) != (r, c)).map(|(rr,cc,x,y)| ((rr, cc), (1u16 << x) | (1u16 << y))).collect();
+    for &(start, xy) in &bivalue {
+        if mask & xy == 0 || !sees(start, (r, c)) { continue; }
+        let chain: Vec<(usize,usize)> = bivalue.iter().filter(|(_, m)| *m == xy).map(|&(p, _)| p).collect();
+        // Breadth-first from `start`, so every cell gets its shortest link count and a way back
+        let mut dist = vec![usize::MAX; chain.len()];
+        let mut parent = vec![usize::MAX; chain.len()];
+        let s0 = chain.iter().position(|&p| p == start).unwrap();
+        dist[s0] = 0;
+        let mut queue = std::collections::VecDeque::from([s0]);
+        while let Some(i) = queue.pop_front() {
+            for j in 0..chain.len() {
+                if dist[j] == usize::MAX && sees(chain[i], chain[j]) { dist[j] = dist[i] + 1; parent[j] = i; queue.push_back(j); }
+            }
+        }
+        let Some(end) = (0..chain.len()).find(|&j| dist[j] != usize::MAX && dist[j] >= 3 && dist[j] % 2 == 1 && sees(chain[j], (r, c))) else { continue };
+        let mut path = vec![chain[end]];
+        let mut j = end;
+        while parent[j] != usize::MAX { j = parent[j]; path.push(chain[j]); }
+        path.reverse();
+        let mut digits = (1..=9u8).filter(|&v| xy & (1 << v) != 0);
+        let (x, y) = (digits.next().unwrap(), digits.next().unwrap());
+        let names: Vec<String> = path.iter().map(|&(pr, pc)| format!("r{}c{}", pr+1, pc+1)).collect();
+        return Some((mask & !xy, format!("Remote pair on {}/{} ({})", x, y, names.join("-"))));
+    }
+    None
+}
+
+// Unique rectangle, Type 1: (r,c) and three cells that are bi-value {X,Y} form a rectangle over exactly two
+// boxes. If (r,c) were X or Y too, X and Y could swap around the rectangle and the puzzle would have two
+// solutions, so on a unique puzzle (r,c) cannot be X or Y.
+fn apply_unique_rectangle(b: &Board, r: usize, c: usize, mask: u16) -> Option<(u16, String)> {
+    for r2 in 0..9 { for c2 in 0..9 {
+        if r2 == r || c2 == c { continue; }
+        // Same band or same stack, but not both: two boxes
+        if (r/3 == r2/3) == (c/3 == c2/3) { continue; }
+        let corners = [(r, c2), (r2, c), (r2, c2)];
+        if corners.iter().any(|&(pr, pc)| b.cells[pr][pc].value != 0) { continue; }
+        let xy = mask_from_candidates(b.candidates(r, c2));
+        if xy.count_ones() != 2 || mask & xy != xy || mask == xy { continue; }
+        if corners.iter().any(|&(pr, pc)| mask_from_candidates(b.candidates(pr, pc)) != xy) { continue; }
+        let mut digits = (1..=9u8).filter(|&v| xy & (1 << v) != 0);
+        let (x, y) = (digits.next().unwrap(), digits.next().unwrap());
+        let mut cells = [(r, c), (r, c2), (r2, c), (r2, c2)];
+        cells.sort();
+        let names: Vec<String> = cells.iter().map(|&(pr, pc)| format!("r{}c{}", pr+1, pc+1)).collect();
+        return Some((mask & !xy, format!("Unique rectangle on {}/{} ({})", x, y, names.join(", "))));
+    }}
+    None
+}
+
+// Two distinct cells see each other when they share a row, column, or box
+fn sees(a: (usize,usize), b: (usize,usize)) -> bool {
+    a != b && (a.0==b.0 || a.1==b.1 || (a.0/3==b.0/3 && a.1/3==b.1/3))
+}
+
 fn apply_naked_pairs_all_units(b: &Board, r: usize, c: usize, mask: u16) -> u16 {
     let br=r/3; let bc=c/3;
     let mut m = mask;
@@ -295,15 +856,19 @@ fn apply_naked_pairs_all_units(b: &Board, r: usize, c: usize, mask: u16) -> u16
     m = reduce_by_naked_pairs_unit(m, unit_candidates_box(b, br, bc));
     m
 }
-fn unit_candidates_row(b: &Board, r: usize) -> [[bool;10];9] { let mut out=[[false;10];9]; for c in 0..9 { out[c]=b.candidates(r,c); } out }
-fn unit_candidates_col(b: &Board, c: usize) -> [[bool;10];9] { let mut out=[[false;10];9]; for r in 0..9 { out[r]=b.candidates(r,c); } out }
-fn unit_candidates_box(b: &Board, br: usize, bc: usize) -> [[bool;10];9] { let mut out=[[false;10];9]; let mut i=0; for r in br*3..br*3+3 { for c in bc*3..bc*3+3 { out[i]=b.candidates(r,c); i+=1; }} out }
+// A unit's cells as candidate arrays; filled cells have none, so they can't pass for naked singles
+fn unit_candidates(b: &Board, unit: Unit) -> [[bool;10];9] {
+    unit.positions().map(|(r, c)| if b.cells[r][c].value==0 { b.candidates(r,c) } else { [false;10] })
+}
+fn unit_candidates_row(b: &Board, r: usize) -> [[bool;10];9] { unit_candidates(b, Unit::Row(r)) }
+fn unit_candidates_col(b: &Board, c: usize) -> [[bool;10];9] { unit_candidates(b, Unit::Col(c)) }
+fn unit_candidates_box(b: &Board, br: usize, bc: usize) -> [[bool;10];9] { unit_candidates(b, Unit::Box(br*3 + bc)) }
 
 fn reduce_by_naked_pairs_unit(current_mask: u16, unit_cands: [[bool;10];9]) -> u16 {
     // Find any pair of cells in unit that have exactly two same candidates {a,b}. Eliminate a,b from other cells (including possibly (r,c)).
     let mut pairs = Vec::new();
-    for i in 0..9 {
-        let m = mask_from_candidates(unit_cands[i]);
+    for cand in unit_cands {
+        let m = mask_from_candidates(cand);
         if m.count_ones()==2 { pairs.push(m); }
     }
     // For each candidate pair that occurs in exactly two cells, it's a valid naked pair
@@ -317,6 +882,84 @@ fn reduce_by_naked_pairs_unit(current_mask: u16, unit_cands: [[bool;10];9]) -> u
     new_mask
 }
 
+// Position of (r,c) inside its row, column and box unit arrays, in that order
+fn unit_targets(b: &Board, r: usize, c: usize) -> [([[bool;10];9], usize); 3] {
+    [(unit_candidates_row(b, r), c), (unit_candidates_col(b, c), r), (unit_candidates_box(b, r/3, c/3), (r%3)*3 + c%3)]
+}
+
+fn apply_naked_triples_all_units(b: &Board, r: usize, c: usize, mask: u16) -> u16 {
+    unit_targets(b, r, c).into_iter().fold(mask, |m, (unit, i)| reduce_by_naked_triples_unit(m, unit, i))
+}
+
+fn apply_naked_quads_all_units(b: &Board, r: usize, c: usize, mask: u16) -> u16 {
+    unit_targets(b, r, c).into_iter().fold(mask, |m, (unit, i)| reduce_by_naked_quads_unit(m, unit, i))
+}
+
+fn apply_hidden_pairs_all_units(b: &Board, r: usize, c: usize, mask: u16) -> u16 {
+    unit_targets(b, r, c).into_iter().fold(mask, |m, (unit, i)| reduce_by_hidden_pairs_unit(m, unit, i))
+}
+
+fn apply_hidden_triples_all_units(b: &Board, r: usize, c: usize, mask: u16) -> u16 {
+    unit_targets(b, r, c).into_iter().fold(mask, |m, (unit, i)| reduce_by_hidden_triples_unit(m, unit, i))
+}
+
+fn reduce_by_naked_triples_unit(current_mask: u16, unit_cands: [[bool;10];9], target: usize) -> u16 {
+    // Three other cells whose candidates together are exactly three digits own those digits in the unit.
+    // Filled cells have no candidates, so the 2..=3 filter skips them.
+    let masks: Vec<(usize,u16)> = (0..9).filter(|&i| i!=target).map(|i| (i, mask_from_candidates(unit_cands[i])))
+        .filter(|(_, m)| (2..=3).contains(&m.count_ones())).collect();
+    let mut new_mask = current_mask;
+    for i in 0..masks.len() { for j in i+1..masks.len() { for k in j+1..masks.len() {
+        let union = masks[i].1 | masks[j].1 | masks[k].1;
+        if union.count_ones()==3 { new_mask &= !union; }
+    }}}
+    new_mask
+}
+
+fn reduce_by_naked_quads_unit(current_mask: u16, unit_cands: [[bool;10];9], target: usize) -> u16 {
+    // Same idea as naked triples with four cells sharing exactly four digits
+    let masks: Vec<(usize,u16)> = (0..9).filter(|&i| i!=target).map(|i| (i, mask_from_candidates(unit_cands[i])))
+        .filter(|(_, m)| (2..=4).contains(&m.count_ones())).collect();
+    let mut new_mask = current_mask;
+    for i in 0..masks.len() { for j in i+1..masks.len() { for k in j+1..masks.len() { for l in k+1..masks.len() {
+        let union = masks[i].1 | masks[j].1 | masks[k].1 | masks[l].1;
+        if union.count_ones()==4 { new_mask &= !union; }
+    }}}}
+    new_mask
+}
+
+fn reduce_by_hidden_pairs_unit(current_mask: u16, unit_cands: [[bool;10];9], target: usize) -> u16 {
+    // Two digits that only fit in the same two empty cells must fill them, so the target (if it is one
+    // of those cells) can drop its other candidates. Filled cells have no candidates and never count, but an
+    // empty cell down to one candidate does, or its digit would look confined to the other cells.
+    let mut pos = [0u16;10];
+    for (i, cand) in unit_cands.iter().enumerate() {
+        for (v, &ok) in cand.iter().enumerate() { if ok { pos[v] |= 1<<i; } }
+    }
+    let mut new_mask = current_mask;
+    for x in 1..=9 { for y in x+1..=9 {
+        if pos[x].count_ones()==2 && pos[x]==pos[y] && pos[x] & (1<<target) != 0 { new_mask &= (1<<x) | (1<<y); }
+    }}
+    new_mask
+}
+
+fn reduce_by_hidden_triples_unit(current_mask: u16, unit_cands: [[bool;10];9], target: usize) -> u16 {
+    // Three digits that only fit in the same three empty cells must fill them, so those cells
+    // (if the target is one of them) can drop every other candidate.
+    let mut pos = [0u16;10];
+    for (i, cand) in unit_cands.iter().enumerate() {
+        for (v, &ok) in cand.iter().enumerate() { if ok { pos[v] |= 1<<i; } }
+    }
+    let digits: Vec<usize> = (1..=9).filter(|&v| (1..=3).contains(&pos[v].count_ones())).collect();
+    let mut new_mask = current_mask;
+    for i in 0..digits.len() { for j in i+1..digits.len() { for k in j+1..digits.len() {
+        let (x, y, z) = (digits[i], digits[j], digits[k]);
+        let cells = pos[x] | pos[y] | pos[z];
+        if cells.count_ones()==3 && cells & (1<<target) != 0 { new_mask &= (1<<x) | (1<<y) | (1<<z); }
+    }}}
+    new_mask
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -333,7 +976,7 @@ mod tests {
         // Target cell with {1,2,3}
         unit[2][1] = true; unit[2][2] = true; unit[2][3] = true;
         // Fill remaining with some other non-pair candidate to avoid false pairs
-        for i in 3..9 { unit[i][4] = true; } // arbitrary {4}
+        for cell in &mut unit[3..] { cell[4] = true; } // arbitrary {4}
 
         let current_mask: u16 = (1u16 << 1) | (1u16 << 2) | (1u16 << 3);
         let new_mask = reduce_by_naked_pairs_unit(current_mask, unit);
@@ -341,6 +984,52 @@ mod tests {
         assert!(new_mask & (1u16 << 3) != 0, "remaining candidate should be 3");
     }
 
+    #[test]
+    fn naked_triple_reduces_target_to_single() {
+        // Cells {1,2}, {2,3}, {1,3} form a naked triple; the target {1,2,3,4} keeps only 4.
+        let mut unit = [[false; 10]; 9];
+        unit[0][1] = true; unit[0][2] = true;
+        unit[1][2] = true; unit[1][3] = true;
+        unit[2][1] = true; unit[2][3] = true;
+        unit[3][1..=4].fill(true);
+        for cell in &mut unit[4..] { cell[5..=8].fill(true); }
+
+        let current_mask = mask_from_candidates(unit[3]);
+        let new_mask = reduce_by_naked_triples_unit(current_mask, unit, 3);
+        assert_eq!(new_mask, 1u16 << 4);
+    }
+
+    #[test]
+    fn naked_quad_reduces_target_to_single() {
+        // Cells {1,2}, {2,3}, {3,4}, {1,4}: no pair or triple among them, but together a naked quad.
+        let mut unit = [[false; 10]; 9];
+        for (i, (x, y)) in [(1, 2), (2, 3), (3, 4), (1, 4)].into_iter().enumerate() { unit[i][x] = true; unit[i][y] = true; }
+        unit[4][1..=5].fill(true);
+        for cell in &mut unit[5..] { cell[6..=9].fill(true); }
+
+        let current_mask = mask_from_candidates(unit[4]);
+        assert_eq!(reduce_by_naked_pairs_unit(current_mask, unit), current_mask);
+        assert_eq!(reduce_by_naked_triples_unit(current_mask, unit, 4), current_mask);
+        assert_eq!(reduce_by_naked_quads_unit(current_mask, unit, 4), 1u16 << 5);
+    }
+
+    #[test]
+    fn hidden_triple_strips_other_candidates() {
+        // Digits 5, 6, 7 only fit in cells 0..=2, so cell 0 {1,5,6,9} is reduced to {5,6}.
+        let mut unit = [[false; 10]; 9];
+        unit[0][1] = true; unit[0][5] = true; unit[0][6] = true; unit[0][9] = true;
+        unit[1][6] = true; unit[1][7] = true; unit[1][2] = true;
+        unit[2][5] = true; unit[2][7] = true; unit[2][3] = true;
+        for cell in &mut unit[3..] { for v in [1, 2, 3, 4, 8, 9] { cell[v] = true; } }
+
+        let current_mask = mask_from_candidates(unit[0]);
+        let new_mask = reduce_by_hidden_triples_unit(current_mask, unit, 0);
+        assert_eq!(new_mask, (1u16 << 5) | (1u16 << 6));
+        // An empty cell down to {5} elsewhere leaves 5 outside the three cells: no triple
+        unit[3] = [false; 10]; unit[3][5] = true;
+        assert_eq!(reduce_by_hidden_triples_unit(current_mask, unit, 0), current_mask);
+    }
+
     #[test]
     fn claiming_row_eliminates_candidate_in_box() {
         // Set up a board where in row 0, candidate '5' appears only in box (0,0),
@@ -359,4 +1048,135 @@ mod tests {
         let new_mask = apply_locked_pointing_claiming(&b, r, c, mask);
         assert!(new_mask & (1u16 << v) == 0, "candidate {} should be eliminated by claiming", v);
     }
+
+    #[test]
+    fn swordfish_rows_eliminate_candidate_in_cover_column() {
+        // Digit 5 is confined to columns {1,4,7} in rows 1, 4 and 7 (1-based): row 1 -> {1,4}, row 4 -> {4,7},
+        // row 7 -> {1,7}. Every base row has only two positions, yet together they form a Swordfish,
+        // so 5 must go elsewhere than r2c1.
+        let mut b = Board::empty();
+        let fill = [1, 2, 3, 4, 6, 7, 8];
+        for (row, open) in [(0usize, [0usize, 3]), (3, [3, 6]), (6, [0, 6])] {
+            let mut i = 0;
+            for col in 0..9 { if !open.contains(&col) { b.cells[row][col].value = fill[i]; i += 1; } }
+        }
+        let (r, c, v) = (1usize, 0usize, 5u8);
+        assert!(b.candidates(r, c)[v as usize], "precondition: v should be a candidate initially");
+        let mask = mask_from_candidates(b.candidates(r, c));
+
+        let (new_mask, why) = apply_fish(&b, r, c, mask, 3).expect("swordfish should apply");
+        assert!(new_mask & (1u16 << v) == 0, "candidate {} should be eliminated by swordfish", v);
+        assert_eq!(why, "Swordfish on 5 (rows 1,4,7)");
+    }
+
+    #[test]
+    fn x_wing_cols_eliminate_candidate_in_cover_row() {
+        // Digit 5 is confined to rows 1 and 4 (1-based) in columns 2 and 8, so r1c5 cannot be 5.
+        let mut b = Board::empty();
+        let fill = [1, 2, 3, 4, 6, 7, 8];
+        for col in [1usize, 7] {
+            let mut i = 0;
+            for row in 0..9 { if row != 0 && row != 3 { b.cells[row][col].value = fill[i]; i += 1; } }
+        }
+        let (r, c, v) = (0usize, 4usize, 5u8);
+        let mask = mask_from_candidates(b.candidates(r, c));
+        let (new_mask, why) = apply_fish(&b, r, c, mask, 2).expect("x-wing should apply");
+        assert!(new_mask & (1u16 << v) == 0, "candidate {} should be eliminated by x-wing", v);
+        assert_eq!(why, "X-Wing on 5 (cols 2,8)");
+    }
+
+    #[test]
+    fn mrv_fill_solves_hard_seventeen_in_few_assignments() {
+        // 17 clues laid out so that naive row-major search backtracks through a huge tree before the first row settles
+        let b = Board::parse("..............3.85..1.2.......5.7.....4...1...9.......5......73..2.1........4...9").unwrap();
+        let mut filled = b.clone();
+        let mut assignments = 0;
+        assert!(fill_mrv(&mut filled, &mut assignments));
+        assert!(filled.is_solved());
+        assert!(assignments < 100_000, "MRV took {} assignments", assignments);
+        assert_eq!(BacktracingBruteSolver::new().solve_to_completion_mrv(&b).unwrap().cells, filled.cells);
+    }
+
+    #[test]
+    fn xy_wing_is_the_only_progress() {
+        // Mid-solve state where no single or basic reduction applies; the pivot r9c9 with pincers forces Z=4 out.
+        let b = Board::parse("68.4..72.72.6.8..54157926839628431571785293643541678922.637...85319842768.72.6.3.").unwrap();
+        assert!(find_naked_single(&b).is_none() && find_hidden_single(&b).is_none());
+        for r in 0..9 { for c in 0..9 { if b.cells[r][c].value==0 {
+            let mut m = mask_from_candidates(b.candidates(r,c));
+            m = apply_locked_pointing_claiming(&b, r, c, m);
+            m = apply_naked_pairs_all_units(&b, r, c, m);
+            assert!(m.count_ones() > 1, "basic reductions alone should not yield a single at r{}c{}", r+1, c+1);
+        }}}
+
+        let steps = LogicalSolver::new().solve_steps(&b, Some(1));
+        let StepKind::Place{ r, c, v, reason, technique } = &steps.last().unwrap().kind else { panic!("expected a placement") };
+        assert_eq!((reason.as_str(), *technique), ("XY-Wing (pivot r9c9, Z=4)", Technique::XyWing));
+        let solved = BacktracingBruteSolver::new().solve_to_completion(&b).unwrap();
+        assert_eq!(solved.cells[*r][*c].value, *v, "XY-Wing placement should match the solution");
+    }
+
+    #[test]
+    fn simple_coloring_wrap_eliminates_to_single() {
+        // The conjugate chain on 5 colors r6c6 and r9c6 alike; they share column 6, so that color is false and
+        // r5c3, which carries it, is left with 6.
+        let b = Board::parse("5.4.1.39718749365239..5.418.43..197.81.97423.7.93..1846.8.39.41431....2997.14.863").unwrap();
+        let (r, c) = (4usize, 2usize);
+        let mut mask = mask_from_candidates(b.candidates(r, c));
+        for (_, _, reduce) in BASIC_REDUCTIONS { mask = reduce(&b, r, c, mask); }
+        for n in 2..=4 { assert!(apply_fish(&b, r, c, mask, n).is_none()); }
+        assert_eq!(mask, (1<<5) | (1<<6));
+        let (m, why) = apply_simple_coloring(&b, r, c, mask).expect("simple coloring should apply");
+        assert_eq!(m, 1<<6);
+        assert!(why.starts_with("Simple coloring on 5 (color wrap:") && why.contains("r6c6") && why.contains("r9c6"), "{}", why);
+        let solved = BacktracingBruteSolver::new().solve_to_completion(&b).unwrap();
+        assert_eq!(solved.cells[r][c].value, 6);
+    }
+
+    #[test]
+    fn hidden_pair_strips_other_candidates() {
+        // Digits 1 and 2 only fit in cells 0 and 1 of the unit
+        let mut unit = [[false;10];9];
+        let set = |cell: &mut [bool;10], ds: &[usize]| for &d in ds { cell[d] = true; };
+        set(&mut unit[0], &[1,2,5,7]);
+        set(&mut unit[1], &[1,2,9]);
+        set(&mut unit[2], &[5,7,9]);
+        set(&mut unit[3], &[3,5]);
+        set(&mut unit[4], &[3,7,9]);
+        // Cells 5..=7 are filled and have no candidates
+        set(&mut unit[8], &[5,9]);
+        for t in 0..2 {
+            let mask = mask_from_candidates(unit[t]);
+            assert_eq!(reduce_by_hidden_pairs_unit(mask, unit, t), (1<<1) | (1<<2));
+        }
+        let mask = mask_from_candidates(unit[2]);
+        assert_eq!(reduce_by_hidden_pairs_unit(mask, unit, 2), mask);
+        // An empty cell down to {1} is still a place for 1, so 1 and 2 no longer pair up
+        unit[5][1] = true;
+        let mask = mask_from_candidates(unit[0]);
+        assert_eq!(reduce_by_hidden_pairs_unit(mask, unit, 0), mask);
+    }
+
+    #[test]
+    fn w_wing_eliminates_to_single() {
+        // r6c2 holds {5,6} and no basic reduction helps; two unconnected {5,6} cells it sees are tied by a strong
+        // link on 5, so one of them is 6 and r6c2 must be 5.
+        let b = Board::parse("5.4.1.39718749365239..5.418243..197.81.97423.7.932.1846.8.39.41431....2997.14.863").unwrap();
+        assert!(find_naked_single(&b).is_none() && find_hidden_single(&b).is_none());
+        let (r, c) = (5usize, 1usize);
+        let mut mask = mask_from_candidates(b.candidates(r, c));
+        for (_, _, reduce) in BASIC_REDUCTIONS { mask = reduce(&b, r, c, mask); }
+        assert!(mask.count_ones() > 1, "basic reductions alone should not yield a single");
+        let (m, why) = apply_w_wing(&b, r, c, mask).expect("w-wing should apply");
+        assert_eq!((m.count_ones(), why.as_str()), (1, "W-Wing"));
+
+        let steps = LogicalSolver::new().solve_steps(&b, Some(1));
+        let (last, elims) = steps.split_last().unwrap();
+        assert!(matches!(&last.kind, StepKind::Place{ r: 5, c: 1, v: 5, reason, .. } if reason == "W-Wing"));
+        // The eliminations on r6c2 come first, ending with the one W-Wing made; the board is untouched until the placement
+        assert!(elims.iter().all(|s| matches!(&s.kind, StepKind::Eliminate{ r: 5, c: 1, .. }) && s.board == b));
+        assert!(matches!(&elims.last().unwrap().kind, StepKind::Eliminate{ v: 6, reason, .. } if reason == "W-Wing"));
+        let solved = BacktracingBruteSolver::new().solve_to_completion(&b).unwrap();
+        assert_eq!(solved.cells[r][c].value, 5, "W-Wing placement should match the solution");
+    }
 }
@@ -0,0 +1,19 @@
+use crate::board::Board;
+
+/// Check that `solution` is a complete, valid grid that keeps every given of `puzzle`. The error names the
+/// first problem found: an empty cell, a digit repeated in a unit, or a given that was changed.
+pub fn check(puzzle: &Board, solution: &Board) -> Result<(), String> {
+    for r in 0..9 { for c in 0..9 {
+        if solution.cells[r][c].value == 0 { return Err(format!("empty cell at ({}, {})", r+1, c+1)); }
+    }}
+    if let Some(&unit) = solution.invalid_units().first() {
+        let mut seen = [false; 10];
+        let v = unit.positions().iter().map(|&(r, c)| solution.cells[r][c].value as usize).find(|&v| std::mem::replace(&mut seen[v], true)).unwrap_or(0);
+        return Err(format!("duplicate {} in {}", v, unit));
+    }
+    for r in 0..9 { for c in 0..9 {
+        let (given, got) = (puzzle.cells[r][c].value, solution.cells[r][c].value);
+        if given != 0 && given != got { return Err(format!("given {} at ({}, {}) changed to {}", given, r+1, c+1, got)); }
+    }}
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use std::time::Instant;
+use suko_core::{board::Board, dlx::DlxSolver, solver::{BacktrackingSolver, LogicalSolver, Solver, StepKind}};
+
+// (name, puzzle, solvable by logic alone)
+const PUZZLES: [(&str, &str, bool); 3] = [
+    ("easy", "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79", true),
+    ("17-clue", "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......", true),
+    ("inkala", "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..", false),
+];
+
+#[test]
+fn backtracking_effort() {
+    for (name, text, _) in PUZZLES {
+        let b = Board::parse(text).unwrap();
+        let t0 = Instant::now();
+        let stats = BacktrackingSolver::new().solve_stats(&b);
+        let lcv = BacktrackingSolver::with_lcv(true).solve_stats(&b);
+        println!("{:<10} nodes {:>7} backtracks {:>7} depth {:>2} | lcv nodes {:>7} | {:?}", name, stats.nodes, stats.backtracks, stats.max_depth, lcv.nodes, t0.elapsed());
+        assert!(stats.solved && lcv.solved, "{} should be solved", name);
+        assert!(stats.backtracks <= stats.nodes);
+    }
+}
+
+#[test]
+fn logical_effort() {
+    for (name, text, logical) in PUZZLES {
+        let b = Board::parse(text).unwrap();
+        let t0 = Instant::now();
+        let steps = LogicalSolver::new().solve_steps(&b, None);
+        let placed = steps.iter().filter(|s| matches!(s.kind, StepKind::Place{ .. })).count();
+        let end = steps.last().map_or(b.clone(), |s| s.board.clone());
+        println!("{:<10} steps {:>4} placed {:>2} solved {} | {:?}", name, steps.len(), placed, end.is_solved(), t0.elapsed());
+        assert_eq!(end.is_solved(), logical, "{}", name);
+        // Whatever logic placed has to agree with the real solution
+        let solution = DlxSolver::solve(&b).unwrap();
+        assert!((0..9).all(|r| (0..9).all(|c| end.cells[r][c].value == 0 || end.cells[r][c].value == solution.cells[r][c].value)), "{}", name);
+    }
+}
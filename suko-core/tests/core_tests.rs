@@ -1,4 +1,5 @@
-use suko_core::{board::Board, solver::{BacktrackingSolver, LogicalSolver, Solver}};
+use rand::{rngs::StdRng, SeedableRng};
+use suko_core::{batch, dlx::DlxSolver, hint, killer::{Cage, KillerBoard}, check::wrong_cells, highscores::{self, HighscoreEntry}, board::{diff, parse_sdk, Board, BoardBuilder, Cell, CellMark, Unit}, devlog::{animate_session, read_session_file, write_session_file, write_session_markdown, DevLogger, SessionLog}, io::parse_any, library, verify, maze::Maze, packed::PackedBoard, puzzle::{count_solutions_board, difficulty_profile, difficulty_score, generate_full_grid_seeded, is_minimal, rate_difficulty, solvable_logically, Difficulty, PuzzleGenerator, Symmetry}, solver::{mrv, solve, BacktracingBruteSolver, BacktrackingSolver, LogicalSolver, SolveStats, Solver, SolverOutcome, Step, StepKind, Strategy, Technique}};
 
 fn easy_puzzle() -> &'static str {
     // Known easy puzzle; dots for blanks
@@ -32,3 +33,1024 @@ fn logical_progress_single_step() {
         assert!(last.board.is_valid());
     }
 }
+
+#[test]
+fn backtracking_reports_outcome() {
+    let b = Board::parse(easy_puzzle()).unwrap();
+    let mut solver = BacktrackingSolver::new();
+    let (steps, outcome) = solver.solve_with_outcome(&b, None);
+    assert_eq!(outcome, SolverOutcome::Solved);
+    assert!(steps.last().unwrap().board.is_solved());
+
+    let (steps, outcome) = solver.solve_with_outcome(&b, Some(3));
+    assert_eq!(outcome, SolverOutcome::Incomplete);
+    assert_eq!(steps.len(), 3);
+
+    // Two 5s in the first row: no search can fix the givens
+    let mut bad = b.clone();
+    bad.cells[0][2].value = 5;
+    let (_, outcome) = solver.solve_with_outcome(&bad, None);
+    assert_eq!(outcome, SolverOutcome::Unsolvable);
+}
+
+#[test]
+fn solve_stats_match_recorded_steps() {
+    let mut b = Board::parse(easy_puzzle()).unwrap();
+    // Drop some givens so the search has to guess
+    for c in 0..9 { b.cells[0][c].value = 0; b.cells[4][c].value = 0; }
+    let mut solver = BacktrackingSolver::new();
+    let stats = solver.solve_stats(&b);
+    let (steps, outcome) = solver.solve_with_outcome(&b, None);
+    assert_eq!(outcome, SolverOutcome::Solved);
+    assert!(stats.solved);
+    assert_eq!(stats.nodes, steps.iter().filter(|s| matches!(s.kind, StepKind::Guess{ .. })).count());
+    assert_eq!(stats.backtracks, steps.iter().filter(|s| matches!(s.kind, StepKind::Backtrack)).count());
+    assert!(stats.max_depth >= 1 && stats.max_depth <= stats.nodes);
+
+    let mut bad = b.clone();
+    bad.cells[1][0].value = 6; bad.cells[1][1].value = 6;
+    assert_eq!(solver.solve_stats(&bad), SolveStats::default());
+}
+
+#[test]
+fn lcv_ordering_cuts_search_on_hard_puzzle() {
+    // 17 clues; trying digits 1..9 takes 481 nodes here, least-constraining-value first takes 111
+    let b = Board::parse("4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......").unwrap();
+    let plain = BacktrackingSolver::new().solve_stats(&b);
+    let lcv = BacktrackingSolver::with_lcv(true).solve_stats(&b);
+    assert!(plain.solved && lcv.solved);
+    assert!(lcv.nodes * 2 < plain.nodes, "{:?} vs {:?}", lcv, plain);
+    let (steps, outcome) = BacktrackingSolver::with_lcv(true).solve_with_outcome(&b, None);
+    assert_eq!(outcome, SolverOutcome::Solved);
+    assert_eq!(steps.last().unwrap().board, BacktrackingSolver::new().solve_with_outcome(&b, None).0.last().unwrap().board);
+}
+
+#[test]
+fn cached_candidates_match_recomputed() {
+    let b = Board::parse(easy_puzzle()).unwrap();
+    let (steps, _) = BacktrackingSolver::new().solve_with_outcome(&b, None);
+    let mut cached = steps.last().unwrap().board.clone();
+    cached.recompute_candidates();
+    // Empty the solved grid cell by cell, then refill it, comparing against a cache-less copy each time
+    let order: Vec<(usize, usize)> = (0..81).map(|i| (i / 9, (i * 7) % 9)).collect();
+    let values: Vec<u8> = order.iter().map(|&(r, c)| cached.cells[r][c].value).collect();
+    let check = |cached: &Board| {
+        let mut fresh = cached.clone();
+        fresh.invalidate_candidates();
+        for r in 0..9 { for c in 0..9 { assert_eq!(cached.candidates(r, c), fresh.candidates(r, c), "mismatch at ({}, {})", r, c); } }
+    };
+    for &(r, c) in &order { cached.set_value(r, c, 0); check(&cached); }
+    for (&(r, c), &v) in order.iter().zip(&values) { cached.set_value(r, c, v); check(&cached); }
+}
+
+#[test]
+fn restricted_candidates_survive_placements_but_not_clears() {
+    let mut b = Board::empty();
+    let mut masks = [[0u16; 9]; 9];
+    masks[0][0] = (1<<1) | (1<<2);
+    masks[8][8] = 1<<4;
+    b.restrict_candidates(&masks);
+    let only = |ds: &[usize]| -> [bool; 10] { std::array::from_fn(|v| ds.contains(&v)) };
+    // A placement in the row only takes its digit away
+    b.set_value(0, 5, 1);
+    assert_eq!(b.candidates(0, 0), only(&[2]));
+    // Clearing it rebuilds the row from the grid, so (0, 0) is back to every digit; (8, 8) is no peer and stays narrowed
+    b.set_value(0, 5, 0);
+    assert_eq!(b.candidates(0, 0), only(&[1, 2, 3, 4, 5, 6, 7, 8, 9]));
+    assert_eq!(b.candidates(8, 8), only(&[4]));
+}
+
+#[test]
+fn rated_puzzles() {
+    assert_eq!(rate_difficulty(&Board::parse(easy_puzzle()).unwrap()), Difficulty::Easy);
+    // An empty grid has many solutions, so logic alone cannot finish it
+    assert_eq!(rate_difficulty(&Board::empty()), Difficulty::Expert);
+
+    let mut gen = PuzzleGenerator::new(Some(7));
+    let (b, d) = gen.generate_rated_puzzle(30);
+    assert_eq!(d, rate_difficulty(&b));
+}
+
+#[test]
+fn json_round_trip_keeps_fixed_flags() {
+    let mut b = Board::parse(easy_puzzle()).unwrap();
+    // A user entry: filled but not a given
+    b.cells[0][2].value = 4;
+    let back = Board::from_json(&b.to_json()).expect("from_json");
+    assert_eq!(back, b);
+    assert!(back.cells[0][0].fixed);
+    assert!(!back.cells[0][2].fixed && back.cells[0][2].value == 4);
+    assert!(Board::from_json("not json").is_err());
+}
+
+#[test]
+fn session_markdown_has_summary() {
+    let b = Board::parse(easy_puzzle()).unwrap();
+    let steps = BacktrackingSolver::new().solve_steps(&b, None);
+    let log = SessionLog { title: "Test".into(), puzzle: easy_puzzle().replace('\n', ""), solver_name: "Backtracking".into(), steps, duration_ms: 45 };
+    let counts = log.step_counts();
+    assert_eq!(counts.placements, 0);
+    assert_eq!(counts.guesses + counts.backtracks, log.steps.len());
+    assert!(log.summary().ends_with("solved in 45ms"), "summary: {}", log.summary());
+
+    let dir = std::env::temp_dir().join(format!("suko-session-{}", uuid::Uuid::new_v4()));
+    let path = write_session_markdown(&dir, &log).expect("write");
+    let md = std::fs::read_to_string(&path).unwrap();
+    assert!(md.contains("## Summary") && md.contains(&log.summary()));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn counts_solutions_for_arbitrary_boards() {
+    let b = Board::parse(easy_puzzle()).unwrap();
+    assert_eq!(count_solutions_board(&b, 2), 1);
+    assert_eq!(count_solutions_board(&Board::empty(), 2), 2);
+    let mut bad = b.clone();
+    bad.cells[0][2].value = 5;
+    assert_eq!(count_solutions_board(&bad, 2), 0);
+}
+
+#[test]
+fn counting_ignores_a_cached_board_state() {
+    // A board carrying a candidate cache must count the same as a plain one, not search on stale candidates
+    let mut b = Board::parse(easy_puzzle()).unwrap();
+    b.recompute_candidates();
+    assert_eq!(count_solutions_board(&b, 2), 1);
+    assert!(!is_minimal(&b));
+    b.restrict_candidates(&[[0; 9]; 9]);
+    assert_eq!(count_solutions_board(&b, 2), 1);
+}
+
+#[test]
+fn symmetric_puzzles_keep_their_symmetry() {
+    for sym in [Symmetry::Rotational180, Symmetry::Horizontal, Symmetry::Vertical, Symmetry::Diagonal] {
+        let mut gen = PuzzleGenerator::new(Some(11));
+        let b = gen.generate_symmetric_puzzle(30, sym);
+        for r in 0..9 { for c in 0..9 {
+            let (pr, pc) = sym.partner(r, c);
+            assert_eq!(b.cells[r][c].value == 0, b.cells[pr][pc].value == 0, "{:?} broken at ({}, {})", sym, r, c);
+        }}
+        assert_eq!(count_solutions_board(&b, 2), 1);
+    }
+}
+
+#[test]
+fn symmetric_puzzles_never_go_below_the_target() {
+    // Pairs come off two at a time, so one of these targets is hit from one clue above
+    for sym in [Symmetry::Rotational180, Symmetry::Horizontal, Symmetry::Vertical, Symmetry::Diagonal] {
+        for target in [40, 41] {
+            let b = PuzzleGenerator::new(Some(5)).generate_symmetric_puzzle(target, sym);
+            assert!((target..=target + 1).contains(&b.filled_count()), "{:?} with target {} left {} clues", sym, target, b.filled_count());
+        }
+    }
+}
+
+#[test]
+fn minimize_leaves_no_redundant_clue() {
+    let mut gen = PuzzleGenerator::new(Some(3));
+    let b = gen.minimize(&Board::parse(easy_puzzle()).unwrap());
+    assert_eq!(count_solutions_board(&b, 2), 1);
+    for r in 0..9 { for c in 0..9 { if b.cells[r][c].value != 0 {
+        assert!(b.cells[r][c].fixed);
+        let mut fewer = b.clone();
+        fewer.cells[r][c].value = 0;
+        assert!(count_solutions_board(&fewer, 2) > 1, "clue at ({}, {}) is redundant", r, c);
+    }}}
+    // Not unique to begin with: nothing to strip
+    assert_eq!(gen.minimize(&Board::empty()), Board::empty());
+}
+
+#[test]
+fn replaying_logical_steps_reaches_final_board() {
+    let start = Board::parse(easy_puzzle()).unwrap();
+    let steps = LogicalSolver::new().solve_steps(&start, None);
+    let log = SessionLog { title: "replay".into(), puzzle: easy_puzzle().into(), solver_name: "Logical".into(), steps, duration_ms: 0 };
+    let mut b = start.clone();
+    for s in &log.steps { b.apply_step(s); }
+    assert_eq!(Some(b), Board::from_session_final(&log));
+    let empty = SessionLog { steps: Vec::new(), ..log };
+    assert!(Board::from_session_final(&empty).is_none());
+}
+
+#[test]
+fn checked_brute_rejects_conflicting_givens() {
+    let mut bad = Board::parse(easy_puzzle()).unwrap();
+    bad.cells[0][2].value = 5; // duplicates the 5 at r1c1
+    let mut brute = BacktracingBruteSolver::new();
+    assert_eq!(brute.solve_to_completion_checked(&bad), Err("input grid already contains conflicts".to_string()));
+    let solved = brute.solve_to_completion_checked(&Board::parse(easy_puzzle()).unwrap()).unwrap().unwrap();
+    assert!(solved.is_solved());
+}
+
+#[test]
+fn sdk_header_comments_are_metadata() {
+    let text = "#A Jane Doe\n#S Puzzle Book 3\n#C rated 7 of 10\n".to_string() + easy_puzzle();
+    let sdk = parse_sdk(&text).unwrap();
+    assert_eq!(sdk.board, Board::parse(easy_puzzle()).unwrap());
+    assert_eq!(sdk.meta, vec![
+        ("author".to_string(), "Jane Doe".to_string()),
+        ("source".to_string(), "Puzzle Book 3".to_string()),
+        ("comment".to_string(), "rated 7 of 10".to_string()),
+    ]);
+}
+
+#[test]
+fn logical_callback_sees_every_step_in_order() {
+    let b = Board::parse(easy_puzzle()).unwrap();
+    let mut seen = Vec::new();
+    let steps = LogicalSolver::new().solve_steps_with(&b, Some(5), |s| seen.push(s.index));
+    assert_eq!(seen, steps.iter().map(|s| s.index).collect::<Vec<_>>());
+    assert_eq!(seen, vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn same_seed_gives_same_grid_and_puzzle() {
+    // Highscore reloads regenerate puzzles from (seed, clues), so this must stay reproducible
+    let full = generate_full_grid_seeded(42);
+    assert!(full.is_solved());
+    assert_eq!(full.to_compact(), generate_full_grid_seeded(42).to_compact());
+    assert_eq!(PuzzleGenerator::new(Some(42)).generate_full_grid().to_compact(), full.to_compact());
+    let a = PuzzleGenerator::new(Some(42)).generate_puzzle(30);
+    let b = PuzzleGenerator::new(Some(42)).generate_puzzle(30);
+    assert_eq!(a.to_compact(), b.to_compact());
+    assert_eq!(Board::parse(&a.to_compact()).unwrap(), a);
+}
+
+#[test]
+fn difficulty_profile_tallies_techniques() {
+    let b = Board::parse(easy_puzzle()).unwrap();
+    let (profile, needs_backtracking) = difficulty_profile(&b);
+    assert!(!needs_backtracking);
+    let blanks = b.cells.iter().flatten().filter(|c| c.value == 0).count();
+    assert_eq!(profile.values().sum::<usize>(), blanks);
+    assert!(profile.keys().all(|k| k == "Naked single" || k == "Hidden single"), "singles only: {:?}", profile);
+    // An empty grid has no singles to start from
+    assert_eq!(difficulty_profile(&Board::empty()), (Default::default(), true));
+}
+
+#[test]
+fn maze_grid_bytes_match_a_perfect_maze() {
+    let (w, h) = (7, 5);
+    let g = Maze::generate_recursive_backtracker(w, h, Some(9)).to_grid_bytes();
+    assert_eq!((g.len(), g[0].len()), (2*h+1, 2*w+1));
+    assert_eq!(g[1][1], 0, "start cell should be open");
+    // Every cell is open, plus one carved passage per spanning-tree edge (cells - 1); the rest is wall
+    let walls: usize = g.iter().flatten().map(|&b| b as usize).sum();
+    assert_eq!(walls, (2*w+1)*(2*h+1) - (2*w*h - 1));
+}
+
+#[test]
+fn maze_bfs_path_connects_start_and_goal() {
+    let m = Maze::generate_recursive_backtracker(8, 6, Some(4));
+    let path = m.solve((0, 0), (7, 5)).expect("perfect maze is connected");
+    assert_eq!((path[0], *path.last().unwrap()), ((0, 0), (7, 5)));
+    assert!(path.windows(2).all(|w| w[0].0.abs_diff(w[1].0) + w[0].1.abs_diff(w[1].1) == 1));
+    assert_eq!(m.to_ascii_with_path(&path).matches('*').count(), path.len());
+    assert!(m.solve((0, 0), (8, 0)).is_none());
+}
+
+#[test]
+fn logical_puzzles_respect_max_difficulty() {
+    let mut gen = PuzzleGenerator::new(Some(11));
+    let b = gen.generate_logical_puzzle(36, Difficulty::Easy);
+    assert_eq!(rate_difficulty(&b), Difficulty::Easy);
+    assert_eq!(count_solutions_board(&b, 2), 1);
+}
+
+#[test]
+fn single_file_devlog_appends_entries() {
+    let dir = std::env::temp_dir().join(format!("suko-devlog-{}", uuid::Uuid::new_v4()));
+    let mut log = DevLogger::with_single_file(&dir, true).unwrap();
+    let first = log.write_log("first", &["a"]).unwrap();
+    let second = log.write_log("second", &["b"]).unwrap();
+    assert_eq!(first, second);
+    let text = std::fs::read_to_string(&first).unwrap();
+    assert!(text.starts_with("first") && text.contains("====") && text.contains("second"));
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn null_devlog_counts_without_writing() {
+    let mut log = DevLogger::null();
+    let paths: Vec<_> = (0..3).map(|i| log.write_log(&format!("entry {}", i), &["x"]).unwrap()).collect();
+    assert_eq!(log.index(), 3);
+    assert!(paths.iter().all(|p| !p.exists()));
+    assert_eq!(paths[2].file_name().unwrap(), "devlog3.txt");
+}
+
+#[test]
+fn candidates_pretty_shows_open_candidates_only() {
+    let empty = Board::empty().candidates_pretty();
+    for d in '1'..='9' { assert_eq!(empty.matches(d).count(), 81, "digit {}", d); }
+    let solved = BacktracingBruteSolver::new().solve_to_completion(&Board::parse(easy_puzzle()).unwrap()).unwrap();
+    assert!(!solved.candidates_pretty().contains(|ch: char| ch.is_ascii_digit() || ch == '.'));
+}
+
+#[test]
+fn unit_accessors_keep_fixed_flags_and_positions() {
+    let mut b = Board::parse(easy_puzzle()).unwrap();
+    b.cells[0][2].value = 4; // user entry, not a given
+    assert_eq!(b.row_cells(0).map(|c| c.value), b.row_values(0));
+    assert_eq!(b.col_cells(4).map(|c| c.value), b.col_values(4));
+    assert_eq!(b.box_cells(1, 2).map(|c| c.value), b.box_values(1, 2));
+    assert!(b.row_cells(0)[0].fixed && !b.row_cells(0)[2].fixed);
+    assert_eq!(Unit::all().count(), 27);
+    let in_box: Vec<(usize, usize)> = b.unit(Unit::Box(4)).map(|(r, c, _)| (r, c)).collect();
+    assert_eq!(in_box, vec![(3,3), (3,4), (3,5), (4,3), (4,4), (4,5), (5,3), (5,4), (5,5)]);
+    assert!(Unit::all().all(|u| b.unit(u).count() == 9));
+}
+
+#[test]
+fn parse_any_reads_common_layouts() {
+    let expected = Board::parse(easy_puzzle()).unwrap();
+    let bare = easy_puzzle().replace('\n', "");
+    assert_eq!(parse_any(&bare).unwrap(), expected);
+    assert_eq!(parse_any(easy_puzzle()).unwrap(), expected);
+    let pretty = "\
+*-----------*
+|53.|.7.|...|
+|6..|195|...|
+|.98|...|.6.|
+|---+---+---|
+|8..|.6.|..3|
+|4..|8.3|..1|
+|7..|.2.|..6|
+|---+---+---|
+|.6.|...|28.|
+|...|419|..5|
+|...|.8.|.79|
+*-----------*";
+    assert_eq!(parse_any(pretty).unwrap(), expected);
+    let spaced = "5 3 - | - 7 - | - - -\n".to_string() + &easy_puzzle().lines().skip(1).collect::<Vec<_>>().join("\n");
+    assert_eq!(parse_any(&spaced).unwrap(), expected);
+    assert!(parse_any("board.sdk").is_err());
+}
+
+#[test]
+fn parse_any_keeps_rows_of_dash_blanks() {
+    let mut expected = Board::parse(easy_puzzle()).unwrap();
+    for c in 0..9 { expected.cells[0][c] = Default::default(); }
+    let rest = easy_puzzle().lines().skip(1).collect::<Vec<_>>().join("\n");
+    assert_eq!(parse_any(&format!("---------\n{}", rest)).unwrap(), expected);
+    assert_eq!(parse_any(&format!("--- --- ---\n{}", rest)).unwrap(), expected);
+    assert_eq!(parse_any(&format!("- - - | - - - | - - -\n---------------------\n{}", rest)).unwrap(), expected);
+}
+
+#[test]
+fn annotated_round_trip_keeps_fixed_flags() {
+    let mut b = Board::parse(easy_puzzle()).unwrap();
+    b.cells[0][2].value = 4; // user entry
+    let text = b.to_annotated();
+    assert_eq!(text.lines().count(), 2);
+    let back = Board::from_annotated(&text).unwrap();
+    assert_eq!(back, b);
+    assert!(back.cells[0][0].fixed && !back.cells[0][2].fixed);
+    // A given flag on an empty cell is rejected
+    let bad = format!("{}\nF{}", ".".repeat(81), ".".repeat(80));
+    assert!(Board::from_annotated(&bad).is_err());
+}
+
+#[test]
+fn diff_lists_changed_cells() {
+    let b = Board::parse(easy_puzzle()).unwrap();
+    assert!(diff(&b, &b).is_empty());
+    let steps = LogicalSolver::new().solve_steps(&b, Some(1));
+    let last = steps.last().unwrap();
+    let StepKind::Place{ r, c, v, .. } = last.kind else { panic!("expected a placement") };
+    assert_eq!(diff(&b, &last.board), vec![(r, c, 0, v)]);
+    let mut edited = b.clone();
+    edited.cells[0][0].value = 0; edited.cells[8][8].value = 3;
+    assert_eq!(diff(&b, &edited), vec![(0, 0, 5, 0), (8, 8, 9, 3)]);
+}
+
+#[test]
+fn wrong_cells_flags_entries_that_differ_from_solution() {
+    let b = Board::parse(easy_puzzle()).unwrap();
+    let solution = BacktracingBruteSolver::new().solve_to_completion(&b).unwrap();
+    let mut current = b.clone();
+    assert!(wrong_cells(&current, &solution).is_empty());
+    // (0,2) is 4 in the solution; an entry of 1 is wrong even though it doesn't conflict yet
+    current.cells[0][2].value = 1;
+    current.cells[0][3].value = solution.cells[0][3].value;
+    assert_eq!(wrong_cells(&current, &solution), vec![(0, 2)]);
+}
+
+#[test]
+fn from_url_extracts_grid_from_query() {
+    let expected = Board::parse(easy_puzzle()).unwrap();
+    let grid = expected.to_compact();
+    let url = format!("https://sudoku.example.com/play?level=3&puzzle={}&lang=en", grid);
+    assert_eq!(Board::from_url(&url).unwrap(), expected);
+    assert_eq!(Board::from_url(&format!("  ?bd={}#top", grid.replace('.', "0"))).unwrap(), expected);
+    assert_eq!(Board::from_url(&format!("?p={}", grid.replace('.', "%2E"))).unwrap(), expected);
+    assert_eq!(Board::from_url(&format!("puzzle={}&lang=en", grid)).unwrap(), expected);
+    assert!(Board::from_url("https://sudoku.example.com/play?puzzle=53..7").is_err());
+}
+
+#[test]
+fn filled_count_and_completion_ratio() {
+    assert_eq!(Board::empty().filled_count(), 0);
+    assert_eq!(Board::empty().completion_ratio(), 0.0);
+    let b = Board::parse(easy_puzzle()).unwrap();
+    assert_eq!(b.filled_count(), 30);
+    let solved = BacktracingBruteSolver::new().solve_to_completion(&b).unwrap();
+    assert_eq!(solved.completion_ratio(), 1.0);
+}
+
+#[test]
+fn explain_cell_names_blocking_peers() {
+    let b = Board::parse(easy_puzzle()).unwrap();
+    let why = b.explain_cell(0, 2);
+    assert_eq!(why.len(), 9);
+    // r1c3: 5 and 3 sit in its row, 8 only in its column, 6 and 9 only in its box
+    assert_eq!(why[4], (5, Some((0, 0, "row"))));
+    assert_eq!(why[7], (8, Some((2, 2, "col"))));
+    assert_eq!(why[5], (6, Some((1, 0, "box"))));
+    let cand = b.candidates(0, 2);
+    for (v, blocker) in why { assert_eq!(blocker.is_none(), cand[v as usize]); }
+}
+
+#[test]
+fn mrv_breaks_ties_by_lowest_index() {
+    // Every cell of an empty board has nine candidates, so the first one wins
+    assert_eq!(mrv(&Board::empty()), Some((0, 0)));
+    let b = Board::parse(easy_puzzle()).unwrap();
+    let solved = BacktracingBruteSolver::new().solve_to_completion(&b).unwrap();
+    assert_eq!(mrv(&solved), None);
+    // Two holes in a full grid both have one candidate; the earlier one is picked
+    let mut two = solved.clone();
+    two.cells[8][8].value = 0; two.cells[4][4].value = 0;
+    assert_eq!(mrv(&two), Some((4, 4)));
+}
+
+#[test]
+fn library_loads_parseable_puzzles_only() {
+    let dir = std::env::temp_dir().join(format!("suko-library-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let b = Board::parse(easy_puzzle()).unwrap();
+    std::fs::write(dir.join("b.sdk"), format!("#Aanon\n{}", easy_puzzle())).unwrap();
+    std::fs::write(dir.join("a.txt"), b.to_compact()).unwrap();
+    std::fs::write(dir.join("broken.sdk"), "53..7").unwrap();
+    std::fs::write(dir.join("notes.md"), b.to_compact()).unwrap();
+    let list = library::load_dir(&dir).unwrap();
+    let names: Vec<&str> = list.iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(names, ["a.txt", "b.sdk"]);
+    assert!(list.iter().all(|(_, board)| *board == b));
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(library::load_dir(&dir).is_err());
+}
+
+#[test]
+fn packed_board_round_trips_and_agrees_with_board() {
+    let b = Board::parse(easy_puzzle()).unwrap();
+    let p = PackedBoard::from_board(&b);
+    assert_eq!(p.to_board(), b);
+    assert_eq!(PackedBoard::from_board(&p.to_board()), p);
+    for r in 0..9 { for c in 0..9 {
+        let cand = b.candidates(r, c);
+        let mask = p.candidates(r, c);
+        for (v, &ok) in cand.iter().enumerate().skip(1) { assert_eq!(ok, mask & (1<<v) != 0, "r{}c{} digit {}", r+1, c+1, v); }
+    }}
+    assert!(p.is_valid() && !p.is_solved());
+    let solved = PackedBoard::from_board(&BacktracingBruteSolver::new().solve_to_completion(&b).unwrap());
+    assert!(solved.is_solved());
+    let mut bad = p;
+    bad.0[2] = 5; // second 5 in the first row
+    assert!(!bad.is_valid() && !bad.to_board().is_valid());
+}
+
+#[test]
+fn solve_with_marks_uses_user_restrictions() {
+    let b = Board::parse(easy_puzzle()).unwrap();
+    let mut marks = [[0u16; 9]; 9];
+    let mut solver = LogicalSolver::new();
+    // No marks: same as a plain solve
+    let plain = solver.solve_steps(&b, None);
+    let free = solver.solve_steps_with_marks(&b, &marks, None);
+    assert_eq!(free.len(), plain.len());
+    assert_eq!(free.last().unwrap().board, plain.last().unwrap().board);
+    // r1c3 computes to {1,2,4}; marking only 4 makes it the first naked single
+    marks[0][2] = 1 << 4;
+    let steps = solver.solve_steps_with_marks(&b, &marks, Some(1));
+    assert!(matches!(steps[0].kind, StepKind::Place{ r: 0, c: 2, v: 4, .. }));
+    assert!(steps[0].board.candidates(0, 3)[6] && !steps[0].board.candidates(0, 3)[4]);
+}
+
+#[test]
+fn session_bytes_round_trip() {
+    let b = Board::parse(easy_puzzle()).unwrap();
+    let steps = LogicalSolver::new().solve_steps(&b, Some(5));
+    assert_eq!(steps.iter().filter(|s| matches!(s.kind, StepKind::Place{ .. })).count(), 5);
+    let log = SessionLog { title: "bytes".into(), puzzle: b.to_compact(), solver_name: "Logical".into(), steps, duration_ms: 12 };
+    let back = SessionLog::from_bytes(&log.to_bytes()).unwrap();
+    assert_eq!((back.title.as_str(), back.duration_ms, back.steps.len()), ("bytes", 12, log.steps.len()));
+    assert_eq!(back.summary(), log.summary());
+    // Replaying the loaded steps ends on the same board
+    let mut replay = Board::parse(&back.puzzle).unwrap();
+    for s in &back.steps { replay.apply_step(s); }
+    assert_eq!(replay, log.steps.last().unwrap().board);
+
+    let dir = std::env::temp_dir().join(format!("suko-bytes-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("run.suko");
+    write_session_file(&path, &log).unwrap();
+    assert_eq!(read_session_file(&path).unwrap().steps.len(), log.steps.len());
+    assert!(SessionLog::from_bytes(b"not a session").is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn box_of_matches_box_positions() {
+    let b = Board::empty();
+    for bx in 0..9 {
+        for (r, c) in b.box_positions(bx) { assert_eq!(b.box_of(r, c), bx); }
+    }
+    assert_eq!(b.box_of(4, 7), 5);
+}
+
+fn score(time_ms: u128, clues: Option<usize>, date: &str) -> HighscoreEntry {
+    HighscoreEntry { time_ms, seed: None, clues, date_utc: date.into(), solution_sdk: None }
+}
+
+#[test]
+fn top_n_by_clues_filters_and_sorts() {
+    let list = vec![
+        score(9000, Some(30), "2024-01-03"), score(5000, Some(25), "2024-01-01"), score(7000, Some(30), "2024-01-02"),
+        score(7000, Some(30), "2024-01-01"), score(1000, None, "2024-01-01"),
+    ];
+    let top = highscores::top_n_by_clues(&list, 30, 2);
+    let got: Vec<(u128, &str)> = top.iter().map(|e| (e.time_ms, e.date_utc.as_str())).collect();
+    assert_eq!(got, [(7000, "2024-01-01"), (7000, "2024-01-02")]);
+    assert_eq!(highscores::top_n_by_clues(&list, 25, 10).len(), 1);
+    assert!(highscores::top_n_by_clues(&list, 17, 10).is_empty());
+}
+
+#[test]
+fn insert_dedup_keeps_fastest_per_puzzle() {
+    let seeded = |t: u128| HighscoreEntry { seed: Some("42".into()), ..score(t, Some(30), "2024-01-01") };
+    let mut list = vec![seeded(8000)];
+    highscores::insert_dedup(&mut list, seeded(9000));
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0].time_ms, 8000);
+    highscores::insert_dedup(&mut list, seeded(6000));
+    assert_eq!((list.len(), list[0].time_ms), (1, 6000));
+    // Same seed at another clue count is a different puzzle
+    highscores::insert_dedup(&mut list, HighscoreEntry { clues: Some(25), ..seeded(7000) });
+    assert_eq!(list.len(), 2);
+    // Seedless entries match on the finished grid
+    let grid = |t: u128, sdk: &str| HighscoreEntry { solution_sdk: Some(sdk.into()), ..score(t, Some(30), "2024-01-02") };
+    highscores::insert_dedup(&mut list, grid(5000, "a"));
+    highscores::insert_dedup(&mut list, grid(4000, "a"));
+    highscores::insert_dedup(&mut list, grid(4500, "b"));
+    assert_eq!(list.len(), 4);
+    assert_eq!(list[2].time_ms, 4000);
+}
+
+#[test]
+fn difficulty_score_orders_easy_below_hard() {
+    let easy = Board::parse(easy_puzzle()).unwrap();
+    let blanks = 81 - easy.filled_count() as u32;
+    // Singles only: every placement weighs 1 or 2
+    let score = easy.rate();
+    assert!(score >= blanks && score <= 2 * blanks, "{}", score);
+    // Needs locked candidates and a naked quad, but logic still finishes it
+    let logical = Board::parse(".6...5..18...1.39...3....6.....2.4.994........2.6.9...3.4...8.7..........9.27....").unwrap();
+    assert!(difficulty_profile(&logical).0.contains_key("Naked quad"));
+    assert!(difficulty_score(&logical) < 100, "{}", difficulty_score(&logical));
+    // Inkala's puzzle: logic gets stuck, so search adds its 100
+    let hard = Board::parse("8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..").unwrap();
+    assert!(difficulty_score(&hard) >= 100);
+    assert!(hard.rate() > score);
+}
+
+#[test]
+fn batch_solve_matches_serial_order() {
+    let mut puzzles: Vec<Board> = (0..5u64).map(|seed| PuzzleGenerator::new(Some(seed)).generate_puzzle(30)).collect();
+    puzzles.insert(2, Board::parse(easy_puzzle()).unwrap());
+    let mut bad = Board::parse(easy_puzzle()).unwrap();
+    bad.cells[0][2].value = 5;
+    puzzles.push(bad);
+    let serial: Vec<Option<Board>> = puzzles.iter().map(suko_core::solver::solve).collect();
+    assert_eq!(batch::solve_all(&puzzles, 3), serial);
+    assert_eq!(batch::solve_all(&puzzles, 0), serial);
+    assert!(serial.last().unwrap().is_none());
+    assert!(batch::solve_all(&[], 4).is_empty());
+}
+
+#[test]
+fn reveal_one_only_picks_empty_cells() {
+    let b = Board::parse(easy_puzzle()).unwrap();
+    let solution = BacktracingBruteSolver::new().solve_to_completion(&b).unwrap();
+    let mut current = solution.clone();
+    // Leave two holes; one filled cell is wrong and must still be left alone
+    current.cells[4][4].value = 0;
+    current.cells[8][0].value = 0;
+    current.cells[0][2].value = 1;
+    let mut rng = StdRng::seed_from_u64(7);
+    for _ in 0..20 {
+        let (r, c, v) = hint::reveal_one(&current, &solution, &mut rng).unwrap();
+        assert!((r, c) == (4, 4) || (r, c) == (8, 0));
+        assert_eq!(v, solution.cells[r][c].value);
+    }
+    current.cells[4][4].value = solution.cells[4][4].value;
+    current.cells[8][0].value = solution.cells[8][0].value;
+    assert_eq!(hint::reveal_one(&current, &solution, &mut rng), None);
+}
+
+#[test]
+fn invalid_units_names_the_duplicate() {
+    let mut b = Board::parse(easy_puzzle()).unwrap();
+    assert!(b.invalid_units().is_empty());
+    // (0,0) holds 5; a second 5 at (2,1) clashes in box 0 only, not in row 2 or column 1
+    assert_eq!(b.cells[0][0].value, 5);
+    b.cells[2][1].value = 5;
+    assert_eq!(b.invalid_units(), vec![Unit::Box(0)]);
+    // (0,2) shares row 0 with the 5 at (0,0) and box 0 with both
+    b.cells[2][1].value = 0;
+    b.cells[0][2].value = 5;
+    assert_eq!(b.invalid_units(), vec![Unit::Row(0), Unit::Box(0)]);
+    assert_eq!(Unit::Box(0).to_string(), "Box 1");
+}
+
+#[test]
+fn dlx_agrees_with_backtracking() {
+    let mut puzzles = vec![Board::parse(easy_puzzle()).unwrap(), Board::parse("4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......").unwrap()];
+    puzzles.extend((0..3u64).map(|seed| PuzzleGenerator::new(Some(seed)).generate_puzzle(26)));
+    for b in &puzzles {
+        let (steps, outcome) = BacktrackingSolver::new().solve_with_outcome(b, None);
+        assert_eq!(outcome, SolverOutcome::Solved);
+        assert_eq!(DlxSolver::solve(b).unwrap(), steps.last().unwrap().board);
+        assert_eq!(DlxSolver::count(b, 2), count_solutions_board(b, 2));
+    }
+    // Counts past one solution, and none at all for clashing givens
+    let mut open = Board::parse(easy_puzzle()).unwrap();
+    for c in 0..9 { open.cells[0][c].value = 0; open.cells[1][c].value = 0; }
+    assert_eq!(DlxSolver::count(&open, 5), count_solutions_board(&open, 5));
+    assert_eq!(DlxSolver::count(&Board::empty(), 3), 3);
+    let mut bad = Board::parse(easy_puzzle()).unwrap();
+    bad.cells[0][2].value = 5;
+    assert_eq!(DlxSolver::count(&bad, 2), 0);
+    assert!(DlxSolver::solve(&bad).is_none());
+}
+
+#[test]
+fn solve_finishes_with_search_where_logic_stops() {
+    // Logic makes no progress on Inkala's puzzle, so the whole grid comes from the search
+    let hard = Board::parse("8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..").unwrap();
+    assert_eq!(solve(&hard).unwrap().cells, DlxSolver::solve(&hard).unwrap().cells);
+    // Valid givens, but r1c9 has no digit left
+    let mut stuck = Board::empty();
+    for c in 0..8 { stuck.cells[0][c].value = c as u8 + 1; }
+    stuck.cells[4][8].value = 9;
+    assert!(solve(&stuck).is_none());
+}
+
+#[test]
+fn unique_rectangle_only_with_assume_unique() {
+    // r1c1, r1c4, r2c1 are {1,2} and r2c4 is {1,2,3}: across boxes 1 and 2 the deadly pattern leaves r2c4 = 3
+    let mut marks = [[0u16; 9]; 9];
+    let (xy, xyz) = ((1 << 1) | (1 << 2), (1 << 1) | (1 << 2) | (1 << 3));
+    marks[0][0] = xy; marks[0][3] = xy; marks[1][0] = xy; marks[1][3] = xyz;
+    let b = Board::empty();
+    assert!(LogicalSolver::new().solve_steps_with_marks(&b, &marks, Some(1)).is_empty());
+    let steps = LogicalSolver::with_assume_unique(true).solve_steps_with_marks(&b, &marks, Some(1));
+    let StepKind::Place{ r, c, v, reason, technique } = &steps.last().unwrap().kind else { panic!("expected a placement") };
+    assert_eq!((*r, *c, *v, *technique), (1, 3, 3, Technique::UniqueRectangle));
+    assert_eq!(reason, "Unique rectangle on 1/2 (r1c1, r1c4, r2c1, r2c4)");
+}
+
+#[test]
+fn content_hash_ignores_fixed_flags() {
+    let a = Board::parse(easy_puzzle()).unwrap();
+    let mut b = a.clone();
+    for r in 0..9 { for c in 0..9 { b.cells[r][c].fixed = false; } }
+    assert_eq!(a.content_hash(), b.content_hash());
+    b.cells[0][2].value = 4;
+    assert_ne!(a.content_hash(), b.content_hash());
+    // Pinned so the hash stays stable across releases
+    assert_eq!(Board::empty().content_hash(), 0x0edb_e9ed_be9a_769f);
+    let mut list = vec![("a.sdk".to_string(), a.clone()), ("b.sdk".to_string(), b), ("c.txt".to_string(), a)];
+    library::dedup(&mut list);
+    let names: Vec<&str> = list.iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(names, ["a.sdk", "b.sdk"]);
+}
+
+#[test]
+fn transforms_round_trip_and_keep_validity() {
+    let b = Board::parse(easy_puzzle()).unwrap();
+    let r = b.rotate90();
+    // The top-left given 5 ends up top-right
+    assert_eq!(r.cells[0][8].value, 5);
+    assert!(r.cells[0][8].fixed);
+    assert_eq!(r.rotate90().rotate90().rotate90(), b);
+    assert_eq!(b.transpose().transpose(), b);
+    assert_eq!(b.mirror_horizontal().mirror_horizontal(), b);
+    assert_eq!(b.transpose().mirror_horizontal(), r);
+
+    let solved = BacktracingBruteSolver::new().solve_to_completion(&b).unwrap();
+    let perm = [4, 7, 1, 9, 2, 8, 3, 6, 5];
+    let relabeled = solved.relabel(perm);
+    assert!(relabeled.is_solved());
+    assert_eq!(relabeled.cells[0][0].value, perm[solved.cells[0][0].value as usize - 1]);
+    assert!(b.relabel(perm).is_valid());
+    assert_eq!(b.relabel(perm).filled_count(), b.filled_count());
+}
+
+#[test]
+fn solvable_logically_needs_no_guess() {
+    assert!(solvable_logically(&Board::parse(easy_puzzle()).unwrap()));
+    // Inkala's "world's hardest sudoku": these techniques stall and a guess is needed
+    let hard = Board::parse("8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..").unwrap();
+    assert!(!solvable_logically(&hard));
+    assert_eq!(DlxSolver::count(&hard, 2), 1);
+}
+
+#[test]
+fn load_lenient_skips_bad_entries() {
+    let path = std::env::temp_dir().join(format!("suko-highscores-{}.json", uuid::Uuid::new_v4()));
+    std::fs::write(&path, r#"[
+        {"time_ms": 9000, "seed": 42, "clues": 30, "date_utc": "2024-01-01T00:00:00Z", "solution_sdk": null},
+        {"seed": "7", "clues": 30},
+        {"time_ms": 5000},
+        "not an entry",
+        {"time_ms": "fast", "date_utc": "2024-01-02T00:00:00Z"}
+    ]"#).unwrap();
+    let (list, skipped) = highscores::load_lenient(&path);
+    assert_eq!(skipped, 3);
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[0].seed.as_deref(), Some("42"));
+    assert_eq!((list[1].time_ms, list[1].clues, list[1].date_utc.as_str()), (5000, None, ""));
+    assert_eq!(highscores::load(&path).len(), 2);
+    std::fs::remove_file(&path).unwrap();
+    let (list, skipped) = highscores::load_lenient(&path);
+    assert!(list.is_empty() && skipped == 0);
+}
+
+#[test]
+fn givens_only_drops_user_entries() {
+    let puzzle = Board::parse(easy_puzzle()).unwrap();
+    let mut b = puzzle.clone();
+    b.cells[0][2].value = 4;
+    b.cells[8][0].value = 1;
+    let reset = b.givens_only();
+    assert_eq!(reset, puzzle);
+    assert_eq!(reset.filled_count(), 30);
+    assert!((0..9).all(|r| (0..9).all(|c| (reset.cells[r][c].value != 0) == reset.cells[r][c].fixed)));
+}
+
+#[test]
+fn exact_clue_count_when_feasible() {
+    let mut gen = PuzzleGenerator::new(Some(11));
+    let b = gen.generate_exact_clues(27).unwrap();
+    assert_eq!(b.filled_count(), 27);
+    assert_eq!(count_solutions_board(&b, 2), 1);
+    assert!(gen.generate_exact_clues(16).is_none());
+}
+
+#[test]
+fn animation_has_a_frame_per_step() {
+    let b = Board::parse(easy_puzzle()).unwrap();
+    let steps = LogicalSolver::new().solve_steps(&b, Some(3));
+    let log = SessionLog { title: "anim".into(), puzzle: b.to_compact(), solver_name: "Logical".into(), steps, duration_ms: 0 };
+    let text = animate_session(&log);
+    let frames: Vec<&str> = text.split('\x0c').collect();
+    assert_eq!(frames.len(), log.steps.len() + 1);
+    assert!(frames[0].starts_with("Start\n"));
+    let StepKind::Place{ r, c, v, .. } = &log.steps[0].kind else { panic!("expected a placement") };
+    assert!(frames[1].contains(&format!("Changed: ({}, {}) 0 -> {}", r+1, c+1, v)), "{}", frames[1]);
+}
+
+#[test]
+fn killer_cages_prune_candidates() {
+    // A 2-cell cage summing to 3 can only hold 1 and 2; once one is placed the other is forced
+    let cage = Cage { cells: vec![(0, 0), (0, 1)], sum: 3 };
+    let mut k = KillerBoard::new(Board::empty(), vec![cage, Cage { cells: vec![(4, 4)], sum: 7 }]);
+    let open = |k: &KillerBoard, r, c| (1..=9u8).filter(|&v| k.candidates(r, c)[v as usize]).collect::<Vec<_>>();
+    assert_eq!(open(&k, 0, 0), [1, 2]);
+    assert_eq!(open(&k, 4, 4), [7]);
+    assert_eq!(open(&k, 8, 8).len(), 9);
+    k.board.cells[0][0].value = 1;
+    assert_eq!(open(&k, 0, 1), [2]);
+    assert!(k.is_valid());
+    k.board.cells[0][1].value = 4;
+    assert!(k.board.is_valid() && !k.is_valid());
+    k.board.cells[0][1].value = 2;
+    assert!(k.is_valid());
+}
+
+#[test]
+fn verify_reports_why_a_solution_fails() {
+    let puzzle = Board::parse(easy_puzzle()).unwrap();
+    let solution = BacktracingBruteSolver::new().solve_to_completion(&puzzle).unwrap();
+    assert_eq!(verify::check(&puzzle, &solution), Ok(()));
+
+    let mut holey = solution.clone();
+    holey.cells[2][0].value = 0;
+    assert_eq!(verify::check(&puzzle, &holey), Err("empty cell at (3, 1)".into()));
+
+    let mut dup = solution.clone();
+    dup.cells[0][2].value = 5;
+    assert_eq!(verify::check(&puzzle, &dup), Err("duplicate 5 in Row 1".into()));
+
+    // A different valid grid: relabeling swaps digits everywhere, so the givens no longer match
+    let other = solution.relabel([2, 1, 3, 4, 5, 6, 7, 8, 9]);
+    assert!(verify::check(&puzzle, &other).unwrap_err().starts_with("given "));
+}
+
+#[test]
+fn remote_pair_chain_eliminates_both_digits() {
+    // {1,2} chain r1c1 - r1c4 (row) - r3c5 (box) - r7c5 (column): r1c1 and r7c5 hold one 1 and one 2, so
+    // r7c1, which sees both ends and none of the middle, is left with 3
+    let mut marks = [[0u16; 9]; 9];
+    let xy = (1 << 1) | (1 << 2);
+    for (r, c) in [(0, 0), (0, 3), (2, 4), (6, 4)] { marks[r][c] = xy; }
+    marks[6][0] = xy | (1 << 3);
+    let steps = LogicalSolver::new().solve_steps_with_marks(&Board::empty(), &marks, Some(1));
+    let StepKind::Place{ r, c, v, reason, technique } = &steps.last().unwrap().kind else { panic!("expected a placement") };
+    assert_eq!((*r, *c, *v, *technique), (6, 0, 3, Technique::RemotePair));
+    assert_eq!(reason, "Remote pair on 1/2 (r1c1-r1c4-r3c5-r7c5)");
+}
+
+#[test]
+fn bivalue_cells_lists_two_candidate_cells() {
+    assert!(Board::empty().bivalue_cells().is_empty());
+    let b = Board::parse(easy_puzzle()).unwrap();
+    let all = b.bivalue_cells();
+    // r1c4 sees 5 3 7 in its row, 1 8 4 in its column and 9 in its box: only 2 and 6 remain
+    assert_eq!(all[..3], [(0, 3, 2, 6), (2, 0, 1, 2), (2, 3, 2, 3)]);
+    assert_eq!(all.len(), 13);
+    assert!(all.iter().all(|&(r, c, x, y)| x < y && (1..=9).filter(|&v| b.candidates(r, c)[v]).count() == 2));
+}
+
+#[test]
+fn generation_reports_each_removal() {
+    let mut seen = Vec::new();
+    let b = PuzzleGenerator::new(Some(5)).generate_puzzle_with_progress(30, |n| seen.push(n));
+    assert_eq!(b, PuzzleGenerator::new(Some(5)).generate_puzzle(30));
+    assert_eq!(seen.first(), Some(&80));
+    assert_eq!(seen.last(), Some(&b.filled_count()));
+    assert!(seen.windows(2).all(|w| w[1] == w[0] - 1));
+}
+
+#[test]
+fn builder_rejects_clashes_as_they_happen() {
+    let mut builder = BoardBuilder::new();
+    builder.set(0, 0, 5).unwrap().set(4, 4, 5).unwrap();
+    let err = builder.set(0, 7, 5).unwrap_err().to_string();
+    assert_eq!(err, "5 at (1, 8) clashes with (1, 1) in the same row");
+    assert!(builder.set(9, 0, 1).is_err() && builder.set(0, 1, 10).is_err());
+    // Replacing a cell's own value is no clash, and a refused placement leaves nothing behind
+    builder.set(0, 0, 5).unwrap().set(0, 0, 0).unwrap().set(0, 7, 5).unwrap();
+    let b = builder.build();
+    assert_eq!((b.cells[0][0].value, b.cells[0][7].value, b.cells[0][7].fixed), (0, 5, true));
+    assert_eq!(b.filled_count(), 2);
+    assert!(b.is_valid());
+}
+
+#[test]
+fn logic_report_says_where_it_stalled() {
+    let easy = LogicalSolver::new().solve_report(&Board::parse(easy_puzzle()).unwrap());
+    assert_eq!(easy.empty, 0);
+    assert!(easy.board.is_solved());
+    assert_eq!(easy.summary(), "Logic solved all 81 cells");
+
+    let hard = Board::parse("8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..").unwrap();
+    // Not even a single to start from
+    let report = LogicalSolver::new().solve_report(&hard);
+    assert_eq!((report.empty, report.last_reason.as_deref()), (60, None));
+    assert_eq!(report.board, hard);
+    assert_eq!(report.summary(), "Logic solved 21/81 cells; no logical step applies");
+}
+
+#[test]
+fn parse_strict_rejects_stray_characters() {
+    let expected = Board::parse(easy_puzzle()).unwrap();
+    assert_eq!(Board::parse_strict(easy_puzzle(), '.').unwrap(), expected);
+    assert_eq!(Board::parse_strict(&easy_puzzle().replace('.', "0"), '0').unwrap(), expected);
+    assert_eq!(Board::parse_strict(&easy_puzzle().replace('.', " "), ' ').unwrap(), expected);
+    // The lenient parser skips the letter; strict mode refuses it
+    let stray = easy_puzzle().replacen('7', "a7", 1);
+    assert_eq!(Board::parse(&stray).unwrap(), expected);
+    assert!(Board::parse_strict(&stray, '.').unwrap_err().to_string().starts_with("unexpected 'a'"));
+    // Zeros are not blanks unless asked for
+    assert!(Board::parse_strict(&easy_puzzle().replace('.', "0"), '.').is_err());
+    assert!(Board::parse_strict(easy_puzzle(), '5').is_err());
+}
+
+#[test]
+fn minimal_puzzles_have_no_spare_clue() {
+    let puzzle = PuzzleGenerator::new(Some(3)).generate_puzzle(30);
+    let minimal = PuzzleGenerator::new(Some(3)).minimize(&puzzle);
+    assert!(is_minimal(&minimal));
+    // Any extra clue from the solution is deducible from the rest, so it is redundant
+    let solution = DlxSolver::solve(&minimal).unwrap();
+    let (r, c) = (0..81).map(|i| (i / 9, i % 9)).find(|&(r, c)| minimal.cells[r][c].value == 0).unwrap();
+    let mut extra = minimal.clone();
+    extra.cells[r][c].value = solution.cells[r][c].value;
+    assert!(!is_minimal(&extra));
+    assert!(!is_minimal(&Board::empty()));
+}
+
+#[test]
+fn strategy_order_limits_the_logical_solver() {
+    let b = Board::parse(easy_puzzle()).unwrap();
+    assert!(LogicalSolver::with_strategies(Vec::new()).solve_steps(&b, None).is_empty());
+    // Default order matches listing every strategy explicitly
+    let all = LogicalSolver::with_strategies(Strategy::ALL.to_vec()).solve_steps(&b, None);
+    assert_eq!(all.len(), LogicalSolver::new().solve_steps(&b, None).len());
+    // Hidden singles first: the opening move is a hidden single even though naked singles exist
+    let steps = LogicalSolver::with_strategies(vec![Strategy::HiddenSingle, Strategy::NakedSingle]).solve_steps(&b, Some(1));
+    let StepKind::Place{ reason, .. } = &steps[0].kind else { panic!("expected a placement") };
+    assert!(reason.starts_with("Hidden single"), "{}", reason);
+    let naked_only = LogicalSolver::with_strategies(vec![Strategy::NakedSingle]).solve_steps(&b, None);
+    assert!(naked_only.iter().all(|s| matches!(&s.kind, StepKind::Place{ reason, .. } if reason.starts_with("Naked single"))));
+}
+
+#[test]
+fn single_techniques_can_be_left_out() {
+    // Logic needs one X-Wing on this puzzle; without it the other fish may stand in, but never as an X-Wing
+    let b = Board::parse("6......1.5.........9.8127.5....23..8.719.....2...65...75...4..2............27..9.").unwrap();
+    let solution = DlxSolver::solve(&b).unwrap();
+    let uses_x_wing = |steps: &[Step]| steps.iter().any(|s| matches!(s.kind, StepKind::Place{ technique: Technique::XWing, .. }));
+    assert!(uses_x_wing(&LogicalSolver::new().solve_steps(&b, None)));
+    let without: Vec<Strategy> = Strategy::ALL.into_iter().filter(|&s| s != Strategy::XWing).collect();
+    let steps = LogicalSolver::with_strategies(without).solve_steps(&b, None);
+    assert!(!uses_x_wing(&steps));
+    for s in &steps {
+        if let StepKind::Place{ r, c, v, .. } = s.kind { assert_eq!(v, solution.cells[r][c].value); }
+    }
+}
+
+#[test]
+fn reordered_strategies_place_only_correct_digits() {
+    use Strategy::*;
+    let mut singles_swapped = Strategy::ALL.to_vec();
+    singles_swapped.swap(0, 1);
+    let orders = [
+        singles_swapped,
+        Strategy::ALL.into_iter().rev().collect(),
+        vec![HiddenSingle, XWing, Swordfish, Jellyfish, LockedCandidates, NakedPair, HiddenPair, NakedTriple, HiddenTriple, NakedQuad],
+    ];
+    let b = PuzzleGenerator::new(Some(4)).generate_puzzle(24);
+    let solution = DlxSolver::solve(&b).unwrap();
+    for order in &orders {
+        let steps = LogicalSolver::with_strategies(order.clone()).solve_steps(&b, None);
+        for s in &steps {
+            if let StepKind::Place{ r, c, v, reason, .. } = &s.kind {
+                assert_eq!(*v, solution.cells[*r][*c].value, "{:?}: {} at r{}c{}", order, reason, r+1, c+1);
+            }
+        }
+        // With every strategy listed, only the order changes, not how far logic gets
+        if order.len() == Strategy::ALL.len() {
+            assert_eq!(steps.last().unwrap().board.is_solved(), solvable_logically(&b), "{:?}", order);
+        }
+    }
+}
+
+#[test]
+fn crosshatch_marks_blocked_and_open_cells() {
+    let mut b = Board::empty();
+    b.cells[0][0] = Cell { value: 5, fixed: true };
+    let marks = b.crosshatch(5);
+    assert_eq!(marks[0][0], CellMark::Given(5));
+    assert_eq!((marks[0][8], marks[8][0], marks[2][2]), (CellMark::Blocked, CellMark::Blocked, CellMark::Blocked));
+    assert_eq!(marks[4][4], CellMark::Candidate);
+    // Row, column and box of r1c1 minus the shared cells: 8 + 8 + 4 blocked
+    assert_eq!(marks.iter().flatten().filter(|&&m| m == CellMark::Blocked).count(), 20);
+
+    // Easy puzzle, digit 8: row 3 and columns 4 and 5 hold an 8, leaving r1c6 as the only place in the
+    // top middle box (a hidden single); the middle left box already has its 8 at r4c1
+    let easy = Board::parse(easy_puzzle()).unwrap();
+    let marks = easy.crosshatch(8);
+    let open: Vec<(usize, usize)> = easy.box_positions(1).into_iter().filter(|&(r, c)| marks[r][c] == CellMark::Candidate).collect();
+    assert_eq!(open, [(0, 5)]);
+    assert_eq!(marks[3][0], CellMark::Given(8));
+    assert!(easy.box_positions(3).into_iter().all(|(r, c)| marks[r][c] != CellMark::Candidate));
+    // A player's entry blocks its peers too, but isn't shown as a given
+    let mut played = easy.clone();
+    played.cells[0][2].value = 8;
+    let marks = played.crosshatch(8);
+    assert_eq!(marks[0][2], CellMark::Entered(8));
+    assert_eq!(marks[0][5], CellMark::Blocked);
+}
+
+#[test]
+fn reductions_stay_sound_without_naked_singles() {
+    // Hidden pairs and triples used to skip empty cells down to one candidate, so with naked singles left out
+    // they confined a digit to the wrong cells and placed wrong digits
+    use Strategy::*;
+    let subsets = [LockedCandidates, NakedPair, HiddenPair, NakedTriple, HiddenTriple, NakedQuad];
+    let orders = [subsets.to_vec(), [&[HiddenSingle][..], &subsets].concat(), [&subsets[..], &[NakedSingle]].concat()];
+    for seed in 0..3u64 {
+        let b = PuzzleGenerator::new(Some(seed)).generate_puzzle(24);
+        let solution = DlxSolver::solve(&b).unwrap();
+        for order in &orders {
+            for s in LogicalSolver::with_strategies(order.clone()).solve_steps(&b, None) {
+                if let StepKind::Place{ r, c, v, reason, .. } = &s.kind {
+                    assert_eq!(*v, solution.cells[*r][*c].value, "seed {} {:?}: {} at r{}c{}", seed, order, reason, r+1, c+1);
+                }
+            }
+        }
+    }
+}
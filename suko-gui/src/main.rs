@@ -1,5 +1,5 @@
 use eframe::{egui, App, Frame, NativeOptions};
-use suko_core::{board::Board, solver::{BacktracingBruteSolver, LogicalSolver, Solver, StepKind}, puzzle::PuzzleGenerator, highscores};
+use suko_core::{board::{parse_sdk, Board}, devlog::{read_session_file, write_session_file, SessionLog}, solver::{BacktracingBruteSolver, LogicalSolver, Solver, StepKind}, puzzle::{count_solutions_board, PuzzleGenerator}, highscores};
 use std::time::Instant;
 use std::fs;
 use std::path::PathBuf;
@@ -9,20 +9,52 @@ struct SukoApp {
     sel: (usize, usize),
     puzzle_text: String,
     status: String,
-    original_board: Option<Board>,
     brute: BacktracingBruteSolver,
     show_candidates: bool,
+    peer_highlight: PeerHighlight,
     // Puzzle generator state
     clues_target: usize,
     puzzle_seed_text: String,
     // Timer and progress
     started_at: Option<Instant>,
     used_bruteforce: bool,
+    // An applied hint makes the solve assisted, so it isn't recorded as a manual time
+    used_hint: bool,
     // Highscores side panel state
     highscores: Vec<highscores::HighscoreEntry>,
     selected_hs: Option<usize>,
     // Recent logical step descriptions for user understanding
     recent_steps: Vec<String>,
+    // Next logical placement shown but not applied yet
+    hint: Option<Hint>,
+    // Pencil marks entered by the user (bit v set = v marked), independent of computed candidates
+    user_marks: [[u16;9];9],
+    // Session opened from a .suko file and the index of the next step to replay
+    replay: Option<(SessionLog, usize)>,
+}
+
+// Which cells are shaded around the selection
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PeerHighlight {
+    None,
+    // Row, column and box of the selected cell
+    Unit,
+    // Every cell holding the selected cell's digit
+    SameDigit,
+}
+
+impl PeerHighlight {
+    fn next(self) -> Self { match self { Self::None => Self::Unit, Self::Unit => Self::SameDigit, Self::SameDigit => Self::None } }
+    fn label(self) -> &'static str { match self { Self::None => "None", Self::Unit => "Unit", Self::SameDigit => "Same digit" } }
+}
+
+// A hint is only meaningful for the board it was computed on
+struct Hint {
+    board: Board,
+    r: usize,
+    c: usize,
+    v: u8,
+    reason: String,
 }
 
 impl Default for SukoApp {
@@ -32,22 +64,28 @@ impl Default for SukoApp {
             sel: (0,0),
             puzzle_text: String::new(),
             status: String::new(),
-            original_board: None,
             brute: BacktracingBruteSolver::new(),
             show_candidates: false,
+            peer_highlight: PeerHighlight::Unit,
             clues_target: 30,
             puzzle_seed_text: String::new(),
             started_at: None,
             used_bruteforce: false,
+            used_hint: false,
             highscores: highscores::load("highscores.json"),
             selected_hs: None,
             recent_steps: Vec::new(),
+            hint: None,
+            user_marks: [[0;9];9],
+            replay: None,
         }
     }
 }
 
 impl App for SukoApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
+        // Drop a hint once the board changed (digit typed, step applied, new puzzle...)
+        if self.hint.as_ref().is_some_and(|h| h.board != self.board) { self.hint = None; }
         egui::TopBottomPanel::top("top").show(ctx, |ui| {
             // Subtle visual tweaks for a cleaner grid look
             let mut style = (*ui.ctx().style()).clone();
@@ -67,17 +105,37 @@ impl App for SukoApp {
                         self.board = last.board.clone();
                         if self.started_at.is_none() { self.started_at = Some(Instant::now()); }
                         self.used_bruteforce = false;
-                        let desc = match &last.kind {
-                            StepKind::Place{ r,c,v,reason } => format!("Place {} at ({}, {}) — {}", v, r+1, c+1, reason),
-                            StepKind::Guess{ r,c,v } => format!("Guess {} at ({}, {})", v, r+1, c+1),
-                            StepKind::Backtrack => "Backtrack".to_string(),
-                        };
-                        self.status = format!("{}", desc);
-                        self.push_recent(desc);
+                        // One logical step is any eliminations that led to it followed by the placement
+                        for s in &steps {
+                            let desc = describe_step(&s.kind);
+                            self.status = desc.clone();
+                            self.push_recent(desc);
+                        }
                     } else {
                         self.status = "No logical step available".into();
                     }
                 }
+                if ui.button(egui::RichText::new("Hint").strong()).on_hover_text("Show where the next logical step is and why; click again to apply it").clicked() {
+                    if let Some(h) = self.hint.take() {
+                        self.board.set_value(h.r, h.c, h.v);
+                        self.user_marks[h.r][h.c] = 0;
+                        self.used_hint = true;
+                        if self.started_at.is_none() { self.started_at = Some(Instant::now()); }
+                        let desc = format!("Place {} at ({}, {}) — {}", h.v, h.r+1, h.c+1, h.reason);
+                        self.status = desc.clone();
+                        self.push_recent(desc);
+                        self.record_if_solved();
+                    } else {
+                        let steps = LogicalSolver::new().solve_steps(&self.board, Some(1));
+                        match steps.iter().map(|s| &s.kind).find(|k| !matches!(k, StepKind::Eliminate{ .. })) {
+                            Some(StepKind::Place{ r,c,v,reason, .. }) => {
+                                self.status = format!("Hint: look at ({}, {}) — {}", r+1, c+1, reason);
+                                self.hint = Some(Hint { board: self.board.clone(), r: *r, c: *c, v: *v, reason: reason.clone() });
+                            },
+                            _ => { self.status = "No logical hint available".into(); }
+                        }
+                    }
+                }
                 if ui.button(egui::RichText::new("Auto logical").strong()).on_hover_text("Apply human-style logic until no more progress and list the steps").clicked() {
                     let mut solver = LogicalSolver::new();
                     let steps = solver.solve_steps(&self.board, None);
@@ -87,11 +145,8 @@ impl App for SukoApp {
                         self.used_bruteforce = false;
                         let mut count = 0usize;
                         for s in &steps {
-                            if let StepKind::Place{ r,c,v,reason } = &s.kind {
-                                let desc = format!("Place {} at ({}, {}) — {}", v, r+1, c+1, reason);
-                                self.push_recent(desc);
-                                count += 1;
-                            }
+                            self.push_recent(describe_step(&s.kind));
+                            if matches!(s.kind, StepKind::Place{ .. }) { count += 1; }
                         }
                         self.status = format!("Applied {} logical step(s)", count);
                     } else {
@@ -103,19 +158,15 @@ impl App for SukoApp {
                     if let Some(path) = rfd::FileDialog::new().add_filter("Sudoku", &["sdk","txt"]).pick_file() {
                         match fs::read_to_string(&path) {
                             Ok(raw) => {
-                                match normalize_puzzle_text(&raw) {
-                                    Ok(norm) => {
-                                        match Board::parse(&norm) {
-                                            Ok(b) => {
-                                                self.board = b.clone(); self.sel=(0,0);
-                                                self.puzzle_text = norm;
-                                                self.original_board = Some(b);
-                                                self.status = format!("Loaded puzzle: {}", display_filename(path));
-                                            },
-                                            Err(e) => { self.status = format!("Failed to parse puzzle: {}", e); }
-                                        }
+                                // parse_sdk skips '#' header lines, whose digits would otherwise leak into the grid
+                                match parse_sdk(&raw) {
+                                    Ok(sdk) => {
+                                        self.puzzle_text = board_to_sdk(&sdk.board);
+                                        self.board = sdk.board; self.sel=(0,0); self.user_marks = [[0;9];9]; self.replay = None;
+                                        self.status = format!("Loaded puzzle: {}", display_filename(path));
+                                        if let Some((_, source)) = sdk.meta.iter().find(|(k, _)| k == "source") { self.status.push_str(&format!(" (source: {})", source)); }
                                     },
-                                    Err(msg) => { self.status = format!("{}", msg); }
+                                    Err(e) => { self.status = format!("Failed to parse puzzle: {}", e); }
                                 }
                             },
                             Err(e) => { self.status = format!("Failed to read file: {}", e); }
@@ -124,12 +175,25 @@ impl App for SukoApp {
                 }
                 ui.separator();
                 if ui.button(egui::RichText::new("Backtracing Solve").strong()).on_hover_text("Bruteforce: try 9→1 on first empty cell, backtrack on conflicts").clicked() {
-                    self.used_bruteforce = true;
-                    match self.brute.solve_to_completion(&self.board) {
-                        Some(solved) => { self.board = solved; self.status = "Solved by backtracing".to_string(); },
-                        None => { self.status = "No solution found".to_string(); }
+                    // A wrong entry earlier on leaves no solution; say so rather than a bare "no solution". An invalid grid
+                    // also counts 0, but the checked solve names its clash instead
+                    if count_solutions_board(&self.board, 2) == 0 && self.board.is_valid() {
+                        self.status = "Your entries make this unsolvable — use Reset Puzzle".to_string();
+                    } else {
+                        self.used_bruteforce = true;
+                        match self.brute.solve_to_completion_checked(&self.board) {
+                            Ok(Some(solved)) => { self.board = solved; self.status = "Solved by backtracing".to_string(); },
+                            Ok(None) => { self.status = "No solution found".to_string(); },
+                            Err(e) => { self.status = format!("Cannot solve: {}", e); }
+                        }
                     }
                 }
+                if ui.button(egui::RichText::new("Reset Puzzle").strong()).on_hover_text("Back to the givens, dropping your entries").clicked() {
+                    self.board = self.board.givens_only();
+                    self.user_marks = [[0;9];9];
+                    self.replay = None;
+                    self.status = "Puzzle reset".into();
+                }
                 ui.separator();
                 if ui.button(egui::RichText::new("Save Board…").strong()).on_hover_text("Save current grid as 81-char .sdk").clicked() {
                     if let Some(path) = rfd::FileDialog::new()
@@ -139,16 +203,54 @@ impl App for SukoApp {
                         match fs::write(&path, board_to_sdk(&self.board)) { Ok(_) => self.status = format!("Saved board: {}", display_filename(path)), Err(e) => self.status = format!("Failed to save board: {}", e) }
                     }
                 }
+                if ui.button(egui::RichText::new("Save Session…").strong()).on_hover_text("Solve logically from the current grid and save the steps as a .suko session").clicked() {
+                    let mut solver = LogicalSolver::new();
+                    let t0 = Instant::now();
+                    let steps = solver.solve_steps(&self.board, None);
+                    let log = SessionLog { title: "Suko GUI session".into(), puzzle: board_to_sdk(&self.board), solver_name: solver.name().to_string(), steps, duration_ms: t0.elapsed().as_millis() };
+                    if let Some(path) = rfd::FileDialog::new().add_filter("Suko session", &["suko"]).set_file_name("session.suko").save_file() {
+                        match write_session_file(&path, &log) { Ok(_) => self.status = format!("Saved session ({}): {}", log.summary(), display_filename(path)), Err(e) => self.status = format!("Failed to save session: {}", e) }
+                    }
+                }
+                if ui.button(egui::RichText::new("Open Session…").strong()).on_hover_text("Load a .suko session and replay its steps with Next").clicked() {
+                    if let Some(path) = rfd::FileDialog::new().add_filter("Suko session", &["suko"]).pick_file() {
+                        match read_session_file(&path).and_then(|log| Ok((Board::parse(&log.puzzle)?, log))) {
+                            Ok((b, log)) => {
+                                self.board = b; self.sel = (0,0); self.user_marks = [[0;9];9];
+                                self.status = format!("Loaded session with {} steps: {}", log.steps.len(), display_filename(path));
+                                self.replay = Some((log, 0));
+                            },
+                            Err(e) => { self.status = format!("Failed to open session: {}", e); }
+                        }
+                    }
+                }
+                if let Some((log, next)) = &mut self.replay {
+                    if ui.button(egui::RichText::new(format!("Next ({}/{})", *next + 1, log.steps.len())).strong()).clicked() {
+                        let step = &log.steps[*next];
+                        self.board.apply_step(step);
+                        let desc = describe_step(&step.kind);
+                        *next += 1;
+                        if *next >= log.steps.len() { self.replay = None; }
+                        self.status = desc.clone();
+                        self.push_recent(desc);
+                    }
+                }
                 ui.separator();
                 if ui.button(egui::RichText::new("Clear Board").strong()).on_hover_text("Set all cells to empty").clicked() {
                     self.board = Board::empty();
                     self.sel = (0,0);
+                    self.replay = None;
+                    self.user_marks = [[0;9];9];
                     self.started_at = None;
                     self.used_bruteforce = false;
+                    self.used_hint = false;
                     self.status = "Cleared board".into();
                 }
                 ui.separator();
                 ui.checkbox(&mut self.show_candidates, "Show candidates");
+                if ui.button(format!("Highlight: {}", self.peer_highlight.label())).on_hover_text("Cycle peer highlighting: none, row/column/box, or cells with the same digit").clicked() {
+                    self.peer_highlight = self.peer_highlight.next();
+                }
                 ui.separator();
                 // Highscores viewer
                 if ui.button("View highscores").clicked() {
@@ -190,11 +292,11 @@ impl App for SukoApp {
                         if let Some(seed) = &e.seed {
                             let mut gen = PuzzleGenerator::new(seed.parse::<u64>().ok());
                             self.board = gen.generate_puzzle(e.clues.unwrap_or(self.clues_target));
-                            self.sel=(0,0); self.started_at=None; self.used_bruteforce=false;
+                            self.sel=(0,0); self.user_marks=[[0;9];9]; self.started_at=None; self.used_bruteforce=false; self.used_hint=false; self.replay = None;
                                 self.status = format!("Loaded puzzle from seed {}", seed);
                         } else if let Some(ref sdk) = e.solution_sdk {
                             if let Ok(b) = Board::parse(sdk) {
-                                self.board = b; self.sel=(0,0); self.started_at=None; self.used_bruteforce=false;
+                                self.board = b; self.sel=(0,0); self.user_marks=[[0;9];9]; self.started_at=None; self.used_bruteforce=false; self.used_hint=false; self.replay = None;
                                 self.status = "Loaded finished grid from highscore".into();
                             }
                         }
@@ -229,8 +331,11 @@ impl App for SukoApp {
                         let mut gen = PuzzleGenerator::new(None);
                         self.board = gen.generate_puzzle(self.clues_target);
                         self.sel = (0,0);
+                        self.replay = None;
+                        self.user_marks = [[0;9];9];
                         self.started_at = Some(Instant::now());
                         self.used_bruteforce = false;
+                        self.used_hint = false;
                         self.status = format!("Generated puzzle ~{} clues", self.clues_target);
                     }
                     ui.separator();
@@ -241,44 +346,54 @@ impl App for SukoApp {
                             let mut gen = PuzzleGenerator::new(Some(seed));
                             self.board = gen.generate_puzzle(self.clues_target);
                             self.sel = (0,0);
+                            self.replay = None;
+                            self.user_marks = [[0;9];9];
                             self.started_at = Some(Instant::now());
                             self.used_bruteforce = false;
+                            self.used_hint = false;
                             self.status = format!("Generated seeded puzzle (seed {})", seed);
                         }
                     }
                 });
                 ui.separator();
                 // Error indicator
-                if !self.board.is_valid() {
-                    ui.colored_label(egui::Color32::RED, "Board has conflicts");
+                let invalid = self.board.invalid_units();
+                if !invalid.is_empty() {
+                    let names: Vec<String> = invalid.iter().map(|u| u.to_string()).collect();
+                    let verb = if names.len() == 1 { "has" } else { "have" };
+                    ui.colored_label(egui::Color32::RED, format!("{} {} conflicts", names.join(", "), verb));
                 }
-                draw_board_ui(ui, &mut self.board, &mut self.sel, self.show_candidates);
+                draw_board_ui(ui, &mut self.board, &mut self.sel, self.show_candidates, self.peer_highlight, &self.user_marks, self.hint.as_ref());
 
-            // Keyboard digit entry for selected cell
+            // Keyboard digit entry for selected cell; Shift+digit toggles a pencil mark instead.
+            // Arrows/hjkl move the selection unless a text field has focus.
+            let typing = ui.ctx().wants_keyboard_input();
             ui.input(|i| {
                 for ev in &i.events {
+                    if let egui::Event::Key{ key, pressed: true, modifiers, .. } = ev {
+                        if !typing {
+                            use egui::Key::*;
+                            match key {
+                                ArrowLeft | H => move_sel(&mut self.sel, 0, -1),
+                                ArrowRight | L => move_sel(&mut self.sel, 0, 1),
+                                ArrowUp | K => move_sel(&mut self.sel, -1, 0),
+                                ArrowDown | J => move_sel(&mut self.sel, 1, 0),
+                                _ => {}
+                            }
+                        }
+                        let (r, c) = self.sel;
+                        if let Some(d) = digit_key(*key).filter(|_| modifiers.shift && self.board.cells[r][c].value == 0) {
+                            self.user_marks[r][c] ^= 1<<d;
+                        }
+                    }
                     if let egui::Event::Text(t) = ev {
-                        if let Some(ch) = t.chars().next() {
-                            if ch == '.' || ch == '0' { if !self.board.cells[self.sel.0][self.sel.1].fixed { self.board.cells[self.sel.0][self.sel.1].value=0; } }
-                            if ch.is_ascii_digit() && ('1'..='9').contains(&ch) {
-                                if !self.board.cells[self.sel.0][self.sel.1].fixed {
-                                    self.board.cells[self.sel.0][self.sel.1].value = ch.to_digit(10).unwrap() as u8;
-                                    if self.started_at.is_none() { self.started_at = Some(Instant::now()); }
-                                    if self.board.is_solved() && !self.used_bruteforce {
-                                        let dur_ms = self.started_at.map(|t| Instant::now().duration_since(t).as_millis()).unwrap_or(0);
-                                        let mut hs = highscores::load("highscores.json");
-                                        hs.push(highscores::HighscoreEntry {
-                                            time_ms: dur_ms,
-                                            seed: if self.puzzle_seed_text.trim().is_empty() { None } else { Some(self.puzzle_seed_text.trim().to_string()) },
-                                            clues: Some(self.clues_target),
-                                            date_utc: chrono::Utc::now().to_rfc3339(),
-                                            solution_sdk: if self.puzzle_seed_text.trim().parse::<u64>().ok().is_none() { Some(board_to_sdk(&self.board)) } else { None },
-                                        });
-                                        let _ = highscores::save("highscores.json", &hs);
-                                        self.highscores = hs;
-                                        self.status = format!("Solved manually in {}s — saved to highscores", dur_ms / 1000);
-                                    }
-                                }
+                        if let Some(ch) = t.chars().next().filter(|_| !i.modifiers.shift) {
+                            if (ch == '.' || ch == '0') && !self.board.cells[self.sel.0][self.sel.1].fixed { self.board.cells[self.sel.0][self.sel.1].value=0; self.user_marks[self.sel.0][self.sel.1] = 0; }
+                            if ('1'..='9').contains(&ch) && !self.board.cells[self.sel.0][self.sel.1].fixed {
+                                self.board.cells[self.sel.0][self.sel.1].value = ch.to_digit(10).unwrap() as u8;
+                                self.user_marks[self.sel.0][self.sel.1] = 0;
+                                if self.started_at.is_none() { self.started_at = Some(Instant::now()); }
+                                self.record_if_solved();
                             }
                         }
                     }
@@ -289,8 +404,7 @@ impl App for SukoApp {
         egui::TopBottomPanel::bottom("status").show(ctx, |ui| {
             ui.add_space(4.0);
             ui.horizontal_wrapped(|ui| {
-                let filled = self.board.cells.iter().flatten().filter(|c| c.value != 0).count();
-                let percent = (filled as f32) / 81.0 * 100.0;
+                let percent = self.board.completion_ratio() * 100.0;
                 let secs = self.started_at.map(|t| Instant::now().duration_since(t).as_secs()).unwrap_or(0);
                 let mut msg = if self.status.is_empty() { String::from("Ready") } else { self.status.clone() };
                 msg.push_str(&format!("  |  Progress: {:.1}%  |  Time: {}s", percent, secs));
@@ -307,15 +421,37 @@ impl SukoApp {
         self.recent_steps.push(desc);
         if self.recent_steps.len() > MAX { let overflow = self.recent_steps.len() - MAX; self.recent_steps.drain(0..overflow); }
     }
+
+    // Save the time to highscores once the board is solved without the solver or hints
+    fn record_if_solved(&mut self) {
+        if !self.board.is_solved() || self.used_bruteforce || self.used_hint { return; }
+        let dur_ms = self.started_at.map(|t| Instant::now().duration_since(t).as_millis()).unwrap_or(0);
+        let mut hs = highscores::load("highscores.json");
+        highscores::insert_dedup(&mut hs, highscores::HighscoreEntry {
+            time_ms: dur_ms,
+            seed: if self.puzzle_seed_text.trim().is_empty() { None } else { Some(self.puzzle_seed_text.trim().to_string()) },
+            clues: Some(self.clues_target),
+            date_utc: chrono::Utc::now().to_rfc3339(),
+            solution_sdk: if self.puzzle_seed_text.trim().parse::<u64>().ok().is_none() { Some(board_to_sdk(&self.board)) } else { None },
+        });
+        let _ = highscores::save("highscores.json", &hs);
+        self.highscores = hs;
+        self.status = format!("Solved manually in {}s — saved to highscores", dur_ms / 1000);
+    }
 }
 
-fn draw_board_ui(ui: &mut egui::Ui, board: &mut Board, sel: &mut (usize,usize), show_candidates: bool) {
+fn draw_board_ui(ui: &mut egui::Ui, board: &mut Board, sel: &mut (usize,usize), show_candidates: bool, highlight: PeerHighlight, marks: &[[u16;9];9], hint: Option<&Hint>) {
     let conflicts = board.conflict_mask();
+    let sel_value = board.cells[sel.0][sel.1].value;
     egui::Grid::new("board").num_columns(9).spacing([4.0, 4.0]).show(ui, |ui| {
         for r in 0..9 {
             for c in 0..9 {
                 let v = board.cells[r][c].value;
-                let peers = r==sel.0 || c==sel.1 || (r/3==sel.0/3 && c/3==sel.1/3);
+                let peers = match highlight {
+                    PeerHighlight::None => false,
+                    PeerHighlight::Unit => r==sel.0 || c==sel.1 || (r/3==sel.0/3 && c/3==sel.1/3),
+                    PeerHighlight::SameDigit => v != 0 && v == sel_value,
+                };
                 let txt = if v==0 { "·".to_string() } else { v.to_string() };
                 let mut text = egui::RichText::new(txt).size(22.0);
                 if board.cells[r][c].fixed { text = text.color(egui::Color32::LIGHT_BLUE); }
@@ -325,7 +461,10 @@ fn draw_board_ui(ui: &mut egui::Ui, board: &mut Board, sel: &mut (usize,usize),
                 if *sel==(r,c) {
                     button = button.fill(egui::Color32::from_gray(60)).stroke(egui::Stroke::new(2.0, egui::Color32::LIGHT_BLUE));
                 }
-                let resp = ui.add(button);
+                let hinted = hint.filter(|h| (h.r, h.c) == (r, c));
+                if hinted.is_some() { button = button.stroke(egui::Stroke::new(3.0, egui::Color32::GOLD)); }
+                let mut resp = ui.add(button);
+                if let Some(h) = hinted { resp = resp.on_hover_text(format!("Hint: {}", h.reason)); }
                 if resp.clicked() { *sel=(r,c); }
 
                 // Draw grid lines around the cell
@@ -346,19 +485,20 @@ fn draw_board_ui(ui: &mut egui::Ui, board: &mut Board, sel: &mut (usize,usize),
                 if r == 8 || r % 3 == 2 { p.line_segment([rect.left_bottom(), rect.right_bottom()], stroke_thick); }
                 else { p.line_segment([rect.left_bottom(), rect.right_bottom()], stroke_thin); }
 
-                // Candidates (pencil marks)
-                if show_candidates && board.cells[r][c].value == 0 {
+                // Candidates (pencil marks); the user's own marks win over computed ones
+                if board.cells[r][c].value == 0 && (show_candidates || marks[r][c] != 0) {
                     let cand = board.candidates(r,c);
                     let w = rect.width(); let h = rect.height();
                     for v in 1..=9 {
-                        if cand[v as usize] {
-                            let rr = (v-1) / 3; let cc = (v-1) % 3;
-                            let x = rect.left() + (cc as f32 + 0.5) * (w/3.0);
-                            let y = rect.top() + (rr as f32 + 0.55) * (h/3.0);
-                            let pos = egui::pos2(x, y);
-                            let font = egui::FontId::monospace(11.0);
-                            p.text(pos, egui::Align2::CENTER_CENTER, format!("{}", v), font, egui::Color32::from_gray(170));
-                        }
+                        let color = if marks[r][c] & (1<<v) != 0 { egui::Color32::LIGHT_GREEN }
+                            else if show_candidates && cand[v as usize] { egui::Color32::from_gray(170) }
+                            else { continue };
+                        let rr = (v-1) / 3; let cc = (v-1) % 3;
+                        let x = rect.left() + (cc as f32 + 0.5) * (w/3.0);
+                        let y = rect.top() + (rr as f32 + 0.55) * (h/3.0);
+                        let pos = egui::pos2(x, y);
+                        let font = egui::FontId::monospace(11.0);
+                        p.text(pos, egui::Align2::CENTER_CENTER, format!("{}", v), font, color);
                     }
                 }
             }
@@ -367,26 +507,34 @@ fn draw_board_ui(ui: &mut egui::Ui, board: &mut Board, sel: &mut (usize,usize),
     });
 }
 
-fn board_to_sdk(b: &Board) -> String {
-    let mut s = String::with_capacity(81);
-    for r in 0..9 { for c in 0..9 { let v=b.cells[r][c].value; s.push(if v==0 { '.' } else { char::from(b'0'+v) }); }}
-    s
+// Step the selection, wrapping around the edges like the TUI
+fn move_sel(sel: &mut (usize, usize), dr: isize, dc: isize) {
+    sel.0 = (sel.0 as isize + dr).rem_euclid(9) as usize;
+    sel.1 = (sel.1 as isize + dc).rem_euclid(9) as usize;
 }
 
-fn normalize_puzzle_text(raw: &str) -> Result<String, String> {
-    let mut out = String::with_capacity(81);
-    for ch in raw.chars() {
-        match ch {
-            '1'..='9' => out.push(ch),
-            '0' | '.' => out.push('.'),
-            _ => {}
-        }
-        if out.len() == 81 { break; }
+fn describe_step(kind: &StepKind) -> String {
+    match kind {
+        StepKind::Place{ r,c,v,reason, .. } => format!("Place {} at ({}, {}) — {}", v, r+1, c+1, reason),
+        StepKind::Eliminate{ r,c,v,reason } => format!("Eliminate {} from ({}, {}) — {}", v, r+1, c+1, reason),
+        StepKind::Guess{ r,c,v } => format!("Guess {} at ({}, {})", v, r+1, c+1),
+        StepKind::Backtrack => "Backtrack".to_string(),
     }
-    if out.len() != 81 {
-        return Err(format!("Puzzle must contain 81 characters (digits or .): got {}", out.len()));
+}
+
+fn digit_key(key: egui::Key) -> Option<u8> {
+    use egui::Key::*;
+    match key {
+        Num1 => Some(1), Num2 => Some(2), Num3 => Some(3), Num4 => Some(4), Num5 => Some(5),
+        Num6 => Some(6), Num7 => Some(7), Num8 => Some(8), Num9 => Some(9),
+        _ => None,
     }
-    Ok(out)
+}
+
+fn board_to_sdk(b: &Board) -> String {
+    let mut s = String::with_capacity(81);
+    for r in 0..9 { for c in 0..9 { let v=b.cells[r][c].value; s.push(if v==0 { '.' } else { char::from(b'0'+v) }); }}
+    s
 }
 
 fn display_filename(path: PathBuf) -> String {
@@ -2,36 +2,61 @@ use std::io;
 use std::time::{Duration, Instant};
 use crossterm::{event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers}, execute, terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen}};
 use ratatui::{prelude::*, widgets::*};
-use suko_core::{board::Board, solver::{BacktracingBruteSolver, LogicalSolver, Solver, StepKind}, puzzle::PuzzleGenerator, highscores};
+use suko_core::{board::{diff, Board}, check::wrong_cells, hint::reveal_one, solver::{BacktracingBruteSolver, LogicalSolver, Solver, Step, StepKind}, puzzle::{count_solutions_board, rate_difficulty, Difficulty, PuzzleGenerator}, highscores, io::parse_any};
 use std::fs;
+use serde::{Deserialize, Serialize};
 
-fn draw_board(frame: &mut Frame, area: Rect, board: &Board, sel: (usize, usize)) {
+// Cells drawn in a special color on top of the usual styling
+struct Highlights<'a> {
+    flash: &'a [(usize, usize)],
+    wrong: &'a [(usize, usize)],
+    revealed: &'a [(usize, usize)],
+}
+
+// r and c index the board, the conflict mask and the box/selection arithmetic together
+#[allow(clippy::needless_range_loop)]
+fn draw_board(frame: &mut Frame, area: Rect, board: &Board, sel: (usize, usize), marks: bool, hl: &Highlights) {
     let mut lines: Vec<Line> = Vec::new();
     let conflicts = board.conflict_mask();
+    // With marks on, every cell is three lines tall: empty cells show their candidates as a 3x3 block
+    let sub_rows = if marks { 3 } else { 1 };
     // Top border not drawn; the surrounding Block provides it. We'll draw row separators between 3x3 bands.
     for r in 0..9 {
-        let mut spans: Vec<Span> = Vec::new();
-        for c in 0..9 {
-            let v = board.cells[r][c].value;
-            let ch = if v == 0 { '·' } else { char::from(b'0' + v) };
-            let mut style = Style::default();
-            // Subgrid background hint via gray tone
-            let subgrid_tint = if (r/3 + c/3) % 2 == 0 { Color::DarkGray } else { Color::Reset };
-            if subgrid_tint != Color::Reset { style = style.bg(subgrid_tint); }
-            // peer highlight: same row, col, or box as selected
-            let in_same_row = r == sel.0;
-            let in_same_col = c == sel.1;
-            let in_same_box = (r/3 == sel.0/3) && (c/3 == sel.1/3);
-            if in_same_row || in_same_col || in_same_box { style = style.fg(Color::Gray); }
-            if (r, c) == sel { style = style.fg(Color::Yellow).add_modifier(Modifier::BOLD); }
-            if conflicts[r][c] { style = style.fg(Color::Red).add_modifier(Modifier::BOLD); }
-            if board.cells[r][c].fixed { style = style.fg(Color::Cyan); }
-            spans.push(Span::styled(format!(" {} ", ch), style));
-            // Box vertical separator
-            if c % 3 == 2 && c != 8 { spans.push(Span::styled("┃", Style::default().fg(Color::White))); spans.push(Span::raw(" ")); }
-            else { spans.push(Span::raw("")); }
+        for sub in 0..sub_rows {
+            let mut spans: Vec<Span> = Vec::new();
+            for c in 0..9 {
+                let v = board.cells[r][c].value;
+                let mut style = Style::default();
+                // Subgrid background hint via gray tone
+                let subgrid_tint = if (r/3 + c/3) % 2 == 0 { Color::DarkGray } else { Color::Reset };
+                if subgrid_tint != Color::Reset { style = style.bg(subgrid_tint); }
+                // peer highlight: same row, col, or box as selected
+                let in_same_row = r == sel.0;
+                let in_same_col = c == sel.1;
+                let in_same_box = (r/3 == sel.0/3) && (c/3 == sel.1/3);
+                if in_same_row || in_same_col || in_same_box { style = style.fg(Color::Gray); }
+                if (r, c) == sel { style = style.fg(Color::Yellow).add_modifier(Modifier::BOLD); }
+                if conflicts[r][c] || hl.wrong.contains(&(r, c)) { style = style.fg(Color::Red).add_modifier(Modifier::BOLD); }
+                if board.cells[r][c].fixed { style = style.fg(Color::Cyan); }
+                if hl.revealed.contains(&(r, c)) { style = style.fg(Color::Magenta); }
+                if hl.flash.contains(&(r, c)) { style = style.fg(Color::Green).add_modifier(Modifier::BOLD); }
+                let text = if !marks {
+                    format!(" {} ", if v == 0 { '·' } else { char::from(b'0' + v) })
+                } else if v != 0 {
+                    if sub == 1 { format!(" {} ", v) } else { "   ".to_string() }
+                } else {
+                    let cand = board.candidates(r, c);
+                    (1..=3).map(|i| { let d = (sub * 3 + i) as u8; if cand[d as usize] { char::from(b'0' + d) } else { ' ' } }).collect()
+                };
+                // Marks are dimmed so placed digits still stand out
+                if marks && v == 0 { style = style.add_modifier(Modifier::DIM); }
+                spans.push(Span::styled(text, style));
+                // Box vertical separator
+                if c % 3 == 2 && c != 8 { spans.push(Span::styled("┃", Style::default().fg(Color::White))); spans.push(Span::raw(" ")); }
+                else { spans.push(Span::raw("")); }
+            }
+            lines.push(Line::from(spans));
         }
-        lines.push(Line::from(spans));
         // Heavy horizontal separator between boxes
         if r % 3 == 2 && r != 8 {
             lines.push(Line::from(Span::styled("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━", Style::default().fg(Color::White))));
@@ -42,6 +67,79 @@ fn draw_board(frame: &mut Frame, area: Rect, board: &Board, sel: (usize, usize))
     frame.render_widget(para, area);
 }
 
+// Autosaved on quit and offered for resume on the next start
+const SESSION_FILE: &str = "suko-tui-session.json";
+
+#[derive(Serialize, Deserialize)]
+struct SavedSession {
+    board: Board,
+    // Play time so far; None if the timer never started
+    elapsed_ms: Option<u64>,
+    used_bruteforce: bool,
+    clues_target: usize,
+    recent_steps: Vec<String>,
+    // Older session files predate the stored solution
+    #[serde(default)]
+    solution: Option<Board>,
+}
+
+// A missing or unreadable session file just means starting fresh
+fn load_session(path: &str) -> Option<SavedSession> {
+    fs::read_to_string(path).ok().and_then(|raw| serde_json::from_str(&raw).ok())
+}
+
+// Range for the generator clue target; 17 is the fewest clues a unique sudoku can have
+const MIN_CLUES: usize = 17;
+const MAX_CLUES: usize = 60;
+
+// Board snapshots for Ctrl-Z / Ctrl-Y. Only the grid is restored; the timer and brute-force flag are left alone
+// so undoing cannot reset a running clock or clear a used solver.
+#[derive(Default)]
+struct History { undo: Vec<Board>, redo: Vec<Board> }
+
+impl History {
+    const MAX: usize = 200;
+    fn record(&mut self, b: &Board) {
+        self.undo.push(b.clone());
+        if self.undo.len() > Self::MAX { self.undo.remove(0); }
+        self.redo.clear();
+    }
+    fn undo(&mut self, b: &mut Board) -> bool {
+        let Some(prev) = self.undo.pop() else { return false; };
+        self.redo.push(std::mem::replace(b, prev));
+        true
+    }
+    fn redo(&mut self, b: &mut Board) -> bool {
+        let Some(next) = self.redo.pop() else { return false; };
+        self.undo.push(std::mem::replace(b, next));
+        true
+    }
+    // A different puzzle was loaded; old snapshots belong to another timer/session
+    fn clear(&mut self) { self.undo.clear(); self.redo.clear(); }
+}
+
+// Play timer; time spent paused is kept apart so it never counts toward a highscore
+#[derive(Default)]
+struct Timer { started_at: Option<Instant>, paused_at: Option<Instant>, paused: Duration }
+
+impl Timer {
+    fn restart(&mut self) { *self = Timer { started_at: Some(Instant::now()), ..Timer::default() }; }
+    fn reset(&mut self) { *self = Timer::default(); }
+    fn start_if_idle(&mut self) { if self.started_at.is_none() { self.restart(); } }
+    fn is_paused(&self) -> bool { self.paused_at.is_some() }
+    fn toggle_pause(&mut self) {
+        if let Some(p) = self.paused_at.take() { self.paused += p.elapsed(); }
+        else if self.started_at.is_some() { self.paused_at = Some(Instant::now()); }
+    }
+    // Continue a restored session as if `elapsed` had already been played
+    fn resume_from(&mut self, elapsed: Duration) { *self = Timer { started_at: Instant::now().checked_sub(elapsed), ..Timer::default() }; }
+    fn elapsed(&self) -> Duration {
+        let Some(t) = self.started_at else { return Duration::ZERO };
+        let paused = self.paused + self.paused_at.map(|p| p.elapsed()).unwrap_or_default();
+        t.elapsed().saturating_sub(paused)
+    }
+}
+
 fn try_move_sel(sel: &mut (usize, usize), last_move: &mut Instant, cooldown: Duration, dr: isize, dc: isize) {
     let now = Instant::now();
     if now.duration_since(*last_move) < cooldown { return; }
@@ -83,22 +181,45 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>, board: &m
     let mut last_move = Instant::now() - cooldown;
     let mut status = String::new();
     // Timer & progress state
-    let mut started_at: Option<Instant> = None;
+    let mut timer = Timer::default();
     let mut used_bruteforce = false;
-    let clues_target: usize = 30; // track last generation level
+    let mut clues_target: usize = 30; // clue count for the next p/P generation
+    let mut puzzle_clues = clues_target; // clue count the current puzzle was generated at
     // highscores state
     let mut hs_list: Vec<highscores::HighscoreEntry> = highscores::load("highscores.json");
     hs_list.sort_by_key(|e| e.time_ms);
-    let mut hs_selected: usize = 0; // index into hs_list for selection
+    let mut hs_selected: usize = 0; // index into the shown (filtered) list for selection
+    let mut hs_filter: Option<usize> = None; // clue count to show, None = all
     let mut recent_steps: Vec<String> = Vec::new();
     let mut show_steps_panel = true;
+    let mut show_marks = false; // pencil marks in every empty cell
+    // Solution of the current generated puzzle, for flagging wrong entries with 'v'
+    let mut solution: Option<Board> = None;
+    let mut show_wrong = false;
+    let mut history = History::default();
+    // Cells the last auto logical run filled in, highlighted until the next key
+    let mut flash: Vec<(usize, usize)> = Vec::new();
+    // Cells filled in by the 'H' hint, shown apart from the player's own entries
+    let mut revealed: Vec<(usize, usize)> = Vec::new();
+    // Candidates the user struck out with Alt+digit (bit v = digit v); the logical solver honors them
+    let mut user_elims = [[0u16; 9]; 9];
+    let mut resume_offer = load_session(SESSION_FILE);
+    if resume_offer.is_some() { status = "Resume previous session? y=resume, any other key=start fresh".into(); }
     loop {
+        // Indices into hs_list currently listed, after the clue filter
+        let shown = shown_highscores(&hs_list, hs_filter);
+        if hs_selected >= shown.len() { hs_selected = shown.len().saturating_sub(1); }
+        let marks = solver_marks(&user_elims);
+        let elim_count: u32 = user_elims.iter().flatten().map(|m| m.count_ones()).sum();
+        // Pencil marks and the candidate readout leave out what the user struck
+        let mut shown_board = board.clone();
+        shown_board.restrict_candidates(&marks);
         terminal.draw(|f| {
             // Layout: main area split into left (board) and right (highscores)
             let vchunks = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([
-                    Constraint::Min(18),
+                    Constraint::Min(if show_marks { 31 } else { 18 }),
                     Constraint::Length(6),
                     Constraint::Min(3),
                 ]).split(f.size());
@@ -106,22 +227,24 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>, board: &m
                 .direction(Direction::Horizontal)
                 .constraints(if show_steps_panel { [Constraint::Min(50), Constraint::Length(30), Constraint::Length(48)] } else { [Constraint::Min(50), Constraint::Length(30), Constraint::Length(0)] })
                 .split(vchunks[0]);
-            draw_board(f, hchunks[0], board, *sel);
+            let wrong = match (&solution, show_wrong) { (Some(sol), true) => wrong_cells(board, sol), _ => Vec::new() };
+            draw_board(f, hchunks[0], &shown_board, *sel, show_marks, &Highlights { flash: &flash, wrong: &wrong, revealed: &revealed });
             // Highscores side list
             let mut hs_lines: Vec<Line> = Vec::new();
-            if hs_list.is_empty() {
-                hs_lines.push(Line::from("No highscores yet"));
+            if shown.is_empty() {
+                hs_lines.push(Line::from(if hs_list.is_empty() { "No highscores yet" } else { "No highscores at this clue count" }));
             } else {
-                for (i, e) in hs_list.iter().enumerate() {
+                for (i, e) in shown.iter().map(|&j| &hs_list[j]).enumerate() {
                     let secs = (e.time_ms / 1000) as u64;
                     let style = if i == hs_selected { Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD) } else { Style::default() };
                     let txt = format!("#{:02} {:>4}s clues={:?} seed={}", i+1, secs, e.clues, e.seed.as_deref().unwrap_or("-"));
                     hs_lines.push(Line::styled(txt, style));
                 }
                 hs_lines.push(Line::from(""));
-                hs_lines.push(Line::from("d=delete  r=reload  t=sort by time"));
+                hs_lines.push(Line::from("d=delete  r=reload  t=sort by time  f=filter clues"));
             }
-            let hs_block = Block::default().borders(Borders::ALL).title("Highscores (↑/↓ select, Enter load)");
+            let hs_title = match hs_filter { Some(n) => format!("Highscores, {} clues (↑/↓ select, Enter load)", n), None => "Highscores (↑/↓ select, Enter load)".to_string() };
+            let hs_block = Block::default().borders(Borders::ALL).title(hs_title);
             let hs_para = Paragraph::new(hs_lines).block(hs_block);
             f.render_widget(hs_para, hchunks[1]);
 
@@ -145,18 +268,19 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>, board: &m
             // Help/status
             let mut cand_str = String::new();
             if board.cells[sel.0][sel.1].value==0 {
-                let cand = board.candidates(sel.0, sel.1);
+                let cand = shown_board.candidates(sel.0, sel.1);
                 let mut first=true;
                 for v in 1..=9 { if cand[v as usize] { if !first { cand_str.push(' '); } cand_str.push(char::from(b'0'+v)); first=false; } }
             }
-            let filled = board.cells.iter().flatten().filter(|c| c.value != 0).count();
-            let percent = (filled as f32) / 81.0 * 100.0;
-            let elapsed = started_at.map(|t| Instant::now().duration_since(t).as_secs()).unwrap_or(0);
+            let percent = board.completion_ratio() * 100.0;
+            let elapsed = timer.elapsed().as_secs();
+            let paused_flag = if timer.is_paused() { "  [PAUSED]" } else { "" };
             // Error indicator if board invalid
             let err_flag = if board.is_valid() { "" } else { "  [Invalid!]" };
+            let elim_flag = if elim_count > 0 { format!("  [{} user elimination(s)]", elim_count) } else { String::new() };
             let help_text = format!(
-                "arrows/hjkl=move | 1-9=set | 0/.=clear | o=Open board.sdk | s=Save board.sdk | O=Open path | S=Save path | Tab: focus input | c=Clear | l=Logical step | L=Auto logical | ]=[ toggle steps | b=Backtracing solve | p=Random puzzle | P=Seeded puzzle | q=Quit\nSelected: ({}, {})   Candidates: [{}]   Progress: {:.1}%   Time: {}s{}   Status: {}",
-                sel.0 + 1, sel.1 + 1, cand_str, percent, elapsed, err_flag, status
+                "arrows/hjkl=move | 1-9=set | 0/.=clear | o=Open board.sdk | s=Save board.sdk | O=Open path | S=Save path | Tab: focus input | c=Clear | l=Logical step | L=Auto logical | ]=[ toggle steps | m=Pencil marks | Alt+1-9=Strike candidate | ?=Explain cell | v=Check mistakes | H=Reveal a cell | b=Backtracing solve | Ctrl-Z/Ctrl-Y=Undo/Redo | Space=Pause timer | +/-=Clues ({}) | p=Random puzzle | P=Seeded puzzle | e=Easy puzzle | M=Minimize givens | q=Quit\nSelected: ({}, {})   Candidates: [{}]   Progress: {:.1}%   Time: {}s{}{}{}   Status: {}",
+                clues_target, sel.0 + 1, sel.1 + 1, cand_str, percent, elapsed, paused_flag, err_flag, elim_flag, status
             );
             let title = "Help";
             let help = Paragraph::new(help_text).block(Block::default().borders(Borders::ALL).title(title));
@@ -170,194 +294,356 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>, board: &m
         })?;
 
         if crossterm::event::poll(std::time::Duration::from_millis(100))? {
-            match event::read()? {
-                Event::Key(k) => {
-                    // Path edit mode: capture text safely
-                    if *path_edit {
-                        match (k.code, k.modifiers) {
-                            (KeyCode::Esc, _) => { *path_edit = false; },
-                            (KeyCode::Enter, _) => {
-                                // Try 81 chars first, else treat as path
-                                if let Ok(norm) = super_simplify_normalize(input_str) {
-                                    match Board::parse(&norm) { Ok(b) => { *board=b; *sel=(0,0); status = "Loaded from pasted text".into(); *path_edit = false; }, Err(e) => { status = format!("Parse failed: {}", e); } }
-                                } else {
-                                    match fs::read_to_string(input_str.trim()) {
-                                        Ok(raw) => if let Ok(norm) = super_simplify_normalize(&raw) { if let Ok(b) = Board::parse(&norm) { *board=b; *sel=(0,0); status = format!("Opened {}", input_str.trim()); *path_edit = false; } } else { status = "Input lacks 81 chars".into(); },
-                                        Err(e) => status = format!("Open failed: {}", e),
-                                    }
-                                }
-                            },
-                            (KeyCode::Backspace, _) => { input_str.pop(); },
-                            (KeyCode::Char('s'), m) if m.contains(KeyModifiers::CONTROL) => {
-                                if !input_str.is_empty() {
-                                    match fs::write(input_str.trim(), board_to_sdk(board)) { Ok(_) => status = format!("Saved {}", input_str.trim()), Err(e) => status = format!("Save failed: {}", e) }
-                                }
-                            },
-                            // Do not exit edit mode on Tab; keep focus until Enter/Esc
-                            (KeyCode::Char(ch), _) => { if input_str.len() < 512 { input_str.push(ch); } },
-                            _ => {}
-                        }
-                        continue; // skip other handlers while editing
-                    }
-
-                    // Normal mode (not editing path)
-                    match k.code {
-                        KeyCode::Char('q') => return Ok(()),
-                        KeyCode::Tab => { *path_edit = true; },
-                        KeyCode::Char('o') => {
-                            if let Ok(raw) = fs::read_to_string("board.sdk") {
-                                if let Ok(norm) = super_simplify_normalize(&raw) {
-                                    if let Ok(b) = Board::parse(&norm) { *board = b; *sel=(0,0); }
-                                }
-                            }
-                        },
-                        KeyCode::Char(']') | KeyCode::Char('=') => { show_steps_panel = !show_steps_panel; },
-                        KeyCode::Char('l') => {
-                            let mut solver = LogicalSolver::new();
-                            let steps = solver.solve_steps(board, Some(1));
-                            if let Some(last) = steps.last() {
-                                *board = last.board.clone();
-                                let desc = match &last.kind {
-                                    StepKind::Place{ r,c,v,reason } => format!("Place {} at ({}, {}) — {}", v, r+1, c+1, reason),
-                                    StepKind::Guess{ r,c,v } => format!("Guess {} at ({}, {})", v, r+1, c+1),
-                                    StepKind::Backtrack => "Backtrack".to_string(),
-                                };
-                                status = desc.clone();
-                                recent_steps.push(desc);
-                                if recent_steps.len()>200 { let overflow = recent_steps.len()-200; recent_steps.drain(0..overflow); }
-                            } else { status = "No logical step available".into(); }
-                        },
-                        KeyCode::Char('L') => {
-                            let mut solver = LogicalSolver::new();
-                            let steps = solver.solve_steps(board, None);
-                            if steps.is_empty() { status = "No logical moves found".into(); }
-                            else {
-                                let mut count=0usize;
-                                for s in &steps {
-                                    if let StepKind::Place{ r,c,v,reason } = &s.kind {
-                                        let desc = format!("Place {} at ({}, {}) — {}", v, r+1, c+1, reason);
-                                        recent_steps.push(desc);
-                                        count+=1;
-                                    }
-                                }
-                                if recent_steps.len()>200 { let overflow = recent_steps.len()-200; recent_steps.drain(0..overflow); }
-                                if let Some(last) = steps.last() { *board = last.board.clone(); }
-                                if started_at.is_none() { started_at = Some(Instant::now()); }
-                                status = format!("Applied {} logical step(s)", count);
-                            }
-                        },
-                        KeyCode::Char('x') => { recent_steps.clear(); },
-                        KeyCode::Char('O') => {
-                            if !input_str.is_empty() {
+            if let Event::Key(k) = event::read()? {
+                // Path edit mode: capture text safely
+                if *path_edit {
+                    match (k.code, k.modifiers) {
+                        (KeyCode::Esc, _) => { *path_edit = false; },
+                        (KeyCode::Enter, _) => {
+                            // Try 81 chars (or a copied puzzle URL) first, else treat as path
+                            if let Ok(b) = parse_any(input_str).or_else(|_| Board::from_url(input_str)) {
+                                *board=b; solution = None; *sel=(0,0); history.clear(); revealed.clear(); user_elims = [[0; 9]; 9]; status = "Loaded from pasted text".into(); *path_edit = false;
+                            } else {
                                 match fs::read_to_string(input_str.trim()) {
-                                    Ok(raw) => if let Ok(norm) = super_simplify_normalize(&raw) { if let Ok(b) = Board::parse(&norm) { *board=b; *sel=(0,0); status = format!("Opened {}", input_str.trim()); } } else { status = "Input lacks 81 chars".into(); },
+                                    Ok(raw) => match parse_any(&raw) { Ok(b) => { *board=b; solution = None; *sel=(0,0); history.clear(); revealed.clear(); user_elims = [[0; 9]; 9]; status = format!("Opened {}", input_str.trim()); *path_edit = false; }, Err(e) => status = format!("Parse failed: {}", e) },
                                     Err(e) => status = format!("Open failed: {}", e),
                                 }
                             }
                         },
-                        KeyCode::Char('b') => {
-                            used_bruteforce = true;
-                            if let Some(solved) = brute.solve_to_completion(board) { *board = solved; status = "Solved".into(); } else { status = "No solution".into(); }
-                        },
-                        KeyCode::Char('r') => { hs_list = highscores::load("highscores.json"); hs_list.sort_by_key(|e| e.time_ms); if hs_selected>=hs_list.len() && !hs_list.is_empty() { hs_selected=hs_list.len()-1; } },
-                        KeyCode::Char('t') => { hs_list.sort_by_key(|e| e.time_ms); },
-                        KeyCode::Char('d') => { if hs_selected < hs_list.len() { hs_list.remove(hs_selected); let _ = highscores::save("highscores.json", &hs_list); if hs_selected>=hs_list.len() && !hs_list.is_empty() { hs_selected=hs_list.len()-1; } } },
-                        KeyCode::Char('p') => {
-                            let mut gen = PuzzleGenerator::new(None);
-                            *board = gen.generate_puzzle(clues_target);
-                            *sel = (0,0);
-                            started_at = Some(Instant::now());
-                            used_bruteforce = false;
-                            status = format!("Generated puzzle with ~{} clues", clues_target);
-                        },
-                        KeyCode::Char('P') => {
-                            let seed_text = input_str.trim().to_string();
-                            let seed_num = seed_text.parse::<u64>().ok();
-                            let mut gen = PuzzleGenerator::new(seed_num);
-                            *board = gen.generate_puzzle(clues_target);
-                            *sel = (0,0);
-                            started_at = Some(Instant::now());
-                            used_bruteforce = false;
-                            status = if let Some(n) = seed_num { format!("Generated seeded puzzle (seed {})", n) } else { format!("Generated puzzle (non-numeric seed: '{}')", seed_text) };
-                        },
-                        KeyCode::Char('c') => { *board = Board::empty(); *sel=(0,0); status = "Cleared".into(); },
-                        KeyCode::Left => { try_move_sel(sel, &mut last_move, cooldown, 0, -1); },
-                        KeyCode::Right => { try_move_sel(sel, &mut last_move, cooldown, 0, 1); },
-                        KeyCode::Up => { try_move_sel(sel, &mut last_move, cooldown, -1, 0); },
-                        KeyCode::Down => { try_move_sel(sel, &mut last_move, cooldown, 1, 0); },
-                        KeyCode::Char('h') => { try_move_sel(sel, &mut last_move, cooldown, 0, -1); },
-                        // Note: 'l' is reserved for logical step above; arrow Right or 'L' (auto logical) handle logic; use Right for movement
-                        KeyCode::Char('k') => { try_move_sel(sel, &mut last_move, cooldown, -1, 0); },
-                        KeyCode::Char('j') => { try_move_sel(sel, &mut last_move, cooldown, 1, 0); },
-                        // Navigate highscores list
-                        KeyCode::Char('K') => { if hs_selected>0 { hs_selected -= 1; } },
-                        KeyCode::Char('J') => { if hs_selected+1 < hs_list.len() { hs_selected += 1; } },
-                        KeyCode::PageUp => { if hs_selected >= 5 { hs_selected -= 5; } else { hs_selected=0; } },
-                        KeyCode::PageDown => { let len=hs_list.len(); if hs_selected+5 < len { hs_selected += 5; } else if len>0 { hs_selected=len-1; } },
-                        KeyCode::Enter => {
-                            if !hs_list.is_empty() {
-                                let e = &hs_list[hs_selected];
-                                if let Some(seed_str) = &e.seed {
-                                    let mut gen = PuzzleGenerator::new(seed_str.parse::<u64>().ok());
-                                    *board = gen.generate_puzzle(e.clues.unwrap_or(clues_target));
-                                    *sel=(0,0); started_at=None; used_bruteforce=false; status = format!("Loaded puzzle from seed {}", seed_str);
-                                } else if let Some(ref sdk) = e.solution_sdk {
-                                    if let Ok(b) = Board::parse(sdk) { *board=b; *sel=(0,0); started_at=None; used_bruteforce=false; status = "Loaded finished grid from highscore".into(); }
-                                }
+                        (KeyCode::Backspace, _) => { input_str.pop(); },
+                        (KeyCode::Char('s'), m) if m.contains(KeyModifiers::CONTROL) => {
+                            // An empty path saves nothing; Ctrl-S must not fall through and type an 's'
+                            let path = input_str.trim();
+                            if !path.is_empty() {
+                                match fs::write(path, board_to_sdk(board)) { Ok(_) => status = format!("Saved {}", path), Err(e) => status = format!("Save failed: {}", e) }
                             }
                         },
-                        KeyCode::Char('g') => { for r in 0..9 { for c in 0..9 { let v=board.cells[r][c].value; board.cells[r][c].fixed = v!=0; }} },
-                        KeyCode::Char('u') => { for r in 0..9 { for c in 0..9 { board.cells[r][c].fixed = false; }} },
-                        KeyCode::Char('.') | KeyCode::Char('0') => { if !board.cells[sel.0][sel.1].fixed { board.cells[sel.0][sel.1].value=0; } },
-                        KeyCode::Char(ch) if ch.is_ascii_digit() => {
-                            if ('1'..='9').contains(&ch) && !board.cells[sel.0][sel.1].fixed {
-                                board.cells[sel.0][sel.1].value = ch.to_digit(10).unwrap() as u8;
-                                // Start timer on first manual move if not started
-                                if started_at.is_none() { started_at = Some(Instant::now()); }
-                                // If solved manually (no brute), record highscore
-                                if board.is_solved() && !used_bruteforce {
-                                    let dur_ms = started_at.map(|t| Instant::now().duration_since(t).as_millis()).unwrap_or(0);
-                                    let mut hs = highscores::load("highscores.json");
-                                    hs.push(highscores::HighscoreEntry {
-                                        time_ms: dur_ms,
-                                        seed: None,
-                                        clues: Some(clues_target),
-                                        date_utc: chrono::Utc::now().to_rfc3339(),
-                                        solution_sdk: Some(board_to_sdk(board)),
-                                    });
-                                    let _ = highscores::save("highscores.json", &hs);
-                                    hs_list = hs;
-                                    status = format!("Solved manually in {}s — saved to highscores", dur_ms / 1000);
-                                }
-                            }
-                        },
-                        KeyCode::Char('s') => { let _ = fs::write("board.sdk", board_to_sdk(board)); status = "Saved to board.sdk".into(); },
-                        KeyCode::Char('S') => {
-                            if !input_str.is_empty() {
-                                match fs::write(input_str.trim(), board_to_sdk(board)) { Ok(_) => status = format!("Saved {}", input_str.trim()), Err(e) => status = format!("Save failed: {}", e) }
-                            }
-                        },
-                        KeyCode::Backspace => { if !board.cells[sel.0][sel.1].fixed { board.cells[sel.0][sel.1].value=0; } },
+                        // Do not exit edit mode on Tab; keep focus until Enter/Esc
+                        (KeyCode::Char(ch), _) if input_str.len() < 512 => { input_str.push(ch); },
                         _ => {}
                     }
-                },
-                _ => {}
+                    continue; // skip other handlers while editing
+                }
+
+                // A pending resume offer takes the first key; anything but 'y' declines and is handled as usual
+                if let Some(saved) = resume_offer.take() {
+                    status.clear();
+                    if k.code == KeyCode::Char('y') {
+                        *board = saved.board; *sel = (0,0); history.clear(); revealed.clear(); user_elims = [[0; 9]; 9];
+                        solution = saved.solution;
+                        match saved.elapsed_ms { Some(ms) => timer.resume_from(Duration::from_millis(ms)), None => timer.reset() }
+                        used_bruteforce = saved.used_bruteforce;
+                        clues_target = saved.clues_target.clamp(MIN_CLUES, MAX_CLUES);
+                        puzzle_clues = clues_target;
+                        recent_steps = saved.recent_steps;
+                        status = "Resumed previous session".into();
+                        continue;
+                    }
+                }
+
+                // Normal mode (not editing path)
+                flash.clear();
+                match k.code {
+                    KeyCode::Char('z') if k.modifiers.contains(KeyModifiers::CONTROL) => {
+                        status = if history.undo(board) { "Undone".into() } else { "Nothing to undo".into() };
+                        // Undoing a reveal empties its cell again, so it is no longer shown as revealed
+                        if revealed.last().is_some_and(|&(r, c)| board.cells[r][c].value == 0) { revealed.pop(); }
+                    },
+                    KeyCode::Char('y') if k.modifiers.contains(KeyModifiers::CONTROL) => {
+                        status = if history.redo(board) { "Redone".into() } else { "Nothing to redo".into() };
+                    },
+                    KeyCode::Char('q') => {
+                        // A finished puzzle leaves nothing to resume
+                        if board.is_solved() { let _ = fs::remove_file(SESSION_FILE); }
+                        else {
+                            let saved = SavedSession {
+                                board: board.clone(),
+                                elapsed_ms: timer.started_at.map(|_| timer.elapsed().as_millis() as u64),
+                                used_bruteforce, clues_target, recent_steps: recent_steps.clone(), solution: solution.clone(),
+                            };
+                            if let Ok(json) = serde_json::to_string(&saved) { let _ = fs::write(SESSION_FILE, json); }
+                        }
+                        return Ok(());
+                    },
+                    KeyCode::Char(' ') => {
+                        timer.toggle_pause();
+                        status = if timer.is_paused() { "Timer paused".into() } else { "Timer running".into() };
+                    },
+                    KeyCode::Tab => { *path_edit = true; },
+                    KeyCode::Char('o') => {
+                        if let Ok(raw) = fs::read_to_string("board.sdk") {
+                            if let Ok(b) = parse_any(&raw) { *board = b; solution = None; *sel=(0,0); history.clear(); revealed.clear(); user_elims = [[0; 9]; 9]; }
+                        }
+                    },
+                    KeyCode::Char(']') | KeyCode::Char('=') => { show_steps_panel = !show_steps_panel; },
+                    KeyCode::Char('v') => {
+                        show_wrong = !show_wrong;
+                        status = match (show_wrong, &solution) {
+                            (false, _) => "Mistake check off".into(),
+                            (true, Some(_)) => "Mistake check on: wrong entries shown in red".into(),
+                            (true, None) => "Mistake check on (only generated puzzles have a stored solution)".into(),
+                        };
+                    },
+                    KeyCode::Char('H') => {
+                        match &solution {
+                            None => status = "No stored solution to reveal from (generate a puzzle first)".into(),
+                            Some(sol) => match reveal_one(board, sol, &mut rand::thread_rng()) {
+                                None => status = "No empty cell left to reveal".into(),
+                                Some((r, c, v)) => {
+                                    history.record(board);
+                                    board.set_value(r, c, v);
+                                    revealed.push((r, c));
+                                    // A revealed cell is help, so this attempt no longer counts toward a highscore
+                                    used_bruteforce = true;
+                                    timer.start_if_idle();
+                                    status = format!("Revealed {} at ({}, {}) — highscore disabled for this puzzle", v, r+1, c+1);
+                                },
+                            },
+                        }
+                    },
+                    KeyCode::Char('m') => { show_marks = !show_marks; status = if show_marks { "Pencil marks on".into() } else { "Pencil marks off".into() }; },
+                    KeyCode::Char('l') => {
+                        let mut solver = LogicalSolver::new();
+                        let steps = if elim_count > 0 { solver.solve_steps_with_marks(board, &marks, Some(1)) } else { solver.solve_steps(board, Some(1)) };
+                        if let Some(last) = steps.last() {
+                            history.record(board);
+                            *board = last.board.clone();
+                            // One logical step is any eliminations that led to it followed by the placement
+                            for s in &steps {
+                                let desc = describe_step(&s.kind);
+                                status = desc.clone();
+                                recent_steps.push(desc);
+                            }
+                            if recent_steps.len()>200 { let overflow = recent_steps.len()-200; recent_steps.drain(0..overflow); }
+                        } else { status = "No logical step available".into(); }
+                    },
+                    KeyCode::Char('L') => {
+                        let mut solver = LogicalSolver::new();
+                        // Fill the "What happened" panel as steps are found rather than after the whole run
+                        let mut count=0usize;
+                        let mut on_step = |s: &Step| {
+                            recent_steps.push(describe_step(&s.kind));
+                            if matches!(s.kind, StepKind::Place{ .. }) { count+=1; }
+                        };
+                        let steps = if elim_count > 0 {
+                            let steps = solver.solve_steps_with_marks(board, &marks, None);
+                            steps.iter().for_each(&mut on_step);
+                            steps
+                        } else { solver.solve_steps_with(board, None, on_step) };
+                        if steps.is_empty() { status = "No logical moves found".into(); }
+                        else {
+                            if recent_steps.len()>200 { let overflow = recent_steps.len()-200; recent_steps.drain(0..overflow); }
+                            if let Some(last) = steps.last() {
+                                flash = diff(board, &last.board).into_iter().map(|(r, c, _, _)| (r, c)).collect();
+                                history.record(board); *board = last.board.clone();
+                            }
+                            timer.start_if_idle();
+                            status = format!("Applied {} logical step(s)", count);
+                        }
+                    },
+                    KeyCode::Char('x') => { recent_steps.clear(); },
+                    KeyCode::Char('?') => {
+                        let (r, c) = *sel;
+                        if board.cells[r][c].value != 0 { status = format!("({}, {}) is already filled", r+1, c+1); }
+                        else {
+                            // One line per digit in the "What happened" panel; the status keeps the short version
+                            let mut open = Vec::new();
+                            for (v, blocker) in board.explain_cell(r, c) {
+                                recent_steps.push(match blocker {
+                                    Some((pr, pc, unit)) => format!("({}, {}): {} ruled out by ({}, {}) in the same {}", r+1, c+1, v, pr+1, pc+1, unit),
+                                    None => { open.push(v.to_string()); format!("({}, {}): {} is a candidate", r+1, c+1, v) },
+                                });
+                            }
+                            if recent_steps.len()>200 { let overflow = recent_steps.len()-200; recent_steps.drain(0..overflow); }
+                            show_steps_panel = true;
+                            status = format!("({}, {}) candidates: {}", r+1, c+1, if open.is_empty() { "none".to_string() } else { open.join(" ") });
+                        }
+                    },
+                    KeyCode::Char('O') if !input_str.is_empty() => {
+                        match fs::read_to_string(input_str.trim()) {
+                            Ok(raw) => match parse_any(&raw) { Ok(b) => { *board=b; solution = None; *sel=(0,0); history.clear(); revealed.clear(); user_elims = [[0; 9]; 9]; status = format!("Opened {}", input_str.trim()); }, Err(e) => status = format!("Parse failed: {}", e) },
+                            Err(e) => status = format!("Open failed: {}", e),
+                        }
+                    },
+                    KeyCode::Char('b') => {
+                        used_bruteforce = true;
+                        // Check uniqueness first so a dead end or an ambiguous puzzle is reported as such
+                        let count = count_solutions_board(board, 2);
+                        let result = if count == 0 && board.is_valid() { Ok(None) } else { brute.solve_to_completion_checked(board) };
+                        match result {
+                            Err(e) => status = format!("Cannot solve: {}", e),
+                            Ok(Some(solved)) => {
+                                history.record(board); *board = solved;
+                                status = if count > 1 { "Solved (puzzle has multiple solutions)".into() } else { "Solved".into() };
+                            },
+                            Ok(None) => status = "No solution".into(),
+                        }
+                    },
+                    KeyCode::Char('r') => { hs_list = highscores::load("highscores.json"); hs_list.sort_by_key(|e| e.time_ms); },
+                    KeyCode::Char('t') => { hs_list.sort_by_key(|e| e.time_ms); },
+                    KeyCode::Char('d') => { if let Some(&i) = shown.get(hs_selected) { hs_list.remove(i); let _ = highscores::save("highscores.json", &hs_list); } },
+                    KeyCode::Char('f') => {
+                        hs_filter = match hs_filter { None => Some(25), Some(25) => Some(30), _ => None };
+                        hs_selected = 0;
+                        status = match hs_filter { Some(n) => format!("Highscores: {}-clue puzzles only", n), None => "Highscores: all".into() };
+                    },
+                    KeyCode::Char('p') => {
+                        let mut gen = PuzzleGenerator::new(None);
+                        let (b, difficulty) = gen.generate_rated_puzzle(clues_target);
+                        *board = b;
+                        // Generated puzzles are unique, so solving recovers the full grid they were carved from
+                        solution = brute.solve_to_completion(board);
+                        *sel = (0,0);
+                        history.clear(); revealed.clear(); user_elims = [[0; 9]; 9];
+                        puzzle_clues = clues_target;
+                        timer.restart();
+                        used_bruteforce = false;
+                        status = format!("Generated puzzle with ~{} clues ({})", clues_target, difficulty);
+                    },
+                    KeyCode::Char('P') => {
+                        let seed_text = input_str.trim().to_string();
+                        let seed_num = seed_text.parse::<u64>().ok();
+                        let mut gen = PuzzleGenerator::new(seed_num);
+                        let (b, difficulty) = gen.generate_rated_puzzle(clues_target);
+                        *board = b;
+                        // Generated puzzles are unique, so solving recovers the full grid they were carved from
+                        solution = brute.solve_to_completion(board);
+                        *sel = (0,0);
+                        history.clear(); revealed.clear(); user_elims = [[0; 9]; 9];
+                        puzzle_clues = clues_target;
+                        timer.restart();
+                        used_bruteforce = false;
+                        status = if let Some(n) = seed_num { format!("Generated seeded puzzle (seed {}, {})", n, difficulty) } else { format!("Generated puzzle (non-numeric seed: '{}', {})", seed_text, difficulty) };
+                    },
+                    KeyCode::Char('e') => {
+                        // Easy puzzles are solvable with singles alone, never needing a guess
+                        let mut gen = PuzzleGenerator::new(None);
+                        let b = gen.generate_logical_puzzle(clues_target, Difficulty::Easy);
+                        let difficulty = rate_difficulty(&b);
+                        *board = b;
+                        // Generated puzzles are unique, so solving recovers the full grid they were carved from
+                        solution = brute.solve_to_completion(board);
+                        *sel = (0,0);
+                        history.clear(); revealed.clear(); user_elims = [[0; 9]; 9];
+                        puzzle_clues = clues_target;
+                        timer.restart();
+                        used_bruteforce = false;
+                        status = format!("Generated puzzle with ~{} clues ({})", clues_target, difficulty);
+                    },
+                    KeyCode::Char('+') => { clues_target = (clues_target + 1).min(MAX_CLUES); status = format!("Clue target: {}", clues_target); },
+                    KeyCode::Char('-') => { clues_target = clues_target.saturating_sub(1).max(MIN_CLUES); status = format!("Clue target: {}", clues_target); },
+                    KeyCode::Char('M') => {
+                        let before = board.filled_count();
+                        if count_solutions_board(board, 2) != 1 { status = "Minimize needs a puzzle with a unique solution".into(); }
+                        else {
+                            history.record(board);
+                            *board = PuzzleGenerator::new(None).minimize(board);
+                            let after = board.filled_count();
+                            status = format!("Stripped to {} minimal givens (was {})", after, before);
+                        }
+                    },
+                    KeyCode::Char('c') => { history.record(board); *board = Board::empty(); solution = None; revealed.clear(); user_elims = [[0; 9]; 9]; *sel=(0,0); status = "Cleared".into(); },
+                    KeyCode::Left => { try_move_sel(sel, &mut last_move, cooldown, 0, -1); },
+                    KeyCode::Right => { try_move_sel(sel, &mut last_move, cooldown, 0, 1); },
+                    KeyCode::Up => { try_move_sel(sel, &mut last_move, cooldown, -1, 0); },
+                    KeyCode::Down => { try_move_sel(sel, &mut last_move, cooldown, 1, 0); },
+                    KeyCode::Char('h') => { try_move_sel(sel, &mut last_move, cooldown, 0, -1); },
+                    // Note: 'l' is reserved for logical step above; arrow Right or 'L' (auto logical) handle logic; use Right for movement
+                    KeyCode::Char('k') => { try_move_sel(sel, &mut last_move, cooldown, -1, 0); },
+                    KeyCode::Char('j') => { try_move_sel(sel, &mut last_move, cooldown, 1, 0); },
+                    // Navigate highscores list
+                    KeyCode::Char('K') => { hs_selected = hs_selected.saturating_sub(1); },
+                    KeyCode::Char('J') if hs_selected+1 < shown.len() => { hs_selected += 1; },
+                    KeyCode::PageUp => { if hs_selected >= 5 { hs_selected -= 5; } else { hs_selected=0; } },
+                    KeyCode::PageDown => { let len=shown.len(); if hs_selected+5 < len { hs_selected += 5; } else if len>0 { hs_selected=len-1; } },
+                    KeyCode::Enter => {
+                        if let Some(&i) = shown.get(hs_selected) {
+                            let e = &hs_list[i];
+                            if let Some(seed_str) = &e.seed {
+                                let mut gen = PuzzleGenerator::new(seed_str.parse::<u64>().ok());
+                                puzzle_clues = e.clues.unwrap_or(clues_target);
+                                *board = gen.generate_puzzle(puzzle_clues);
+                                solution = brute.solve_to_completion(board);
+                                *sel=(0,0); history.clear(); revealed.clear(); user_elims = [[0; 9]; 9]; timer.reset(); used_bruteforce=false; status = format!("Loaded puzzle from seed {}", seed_str);
+                            } else if let Some(ref sdk) = e.solution_sdk {
+                                if let Ok(b) = Board::parse(sdk) { *board=b; solution = None; *sel=(0,0); history.clear(); revealed.clear(); user_elims = [[0; 9]; 9]; timer.reset(); used_bruteforce=false; status = "Loaded finished grid from highscore".into(); }
+                            }
+                        }
+                    },
+                    KeyCode::Char('g') => { for r in 0..9 { for c in 0..9 { let v=board.cells[r][c].value; board.cells[r][c].fixed = v!=0; }} },
+                    KeyCode::Char('u') => { for r in 0..9 { for c in 0..9 { board.cells[r][c].fixed = false; }} },
+                    KeyCode::Char('.') | KeyCode::Char('0') if !board.cells[sel.0][sel.1].fixed => { history.record(board); board.cells[sel.0][sel.1].value=0; },
+                    KeyCode::Char(ch @ '1'..='9') if k.modifiers.contains(KeyModifiers::ALT) => {
+                        let (r, c) = *sel;
+                        let v = ch.to_digit(10).unwrap();
+                        if board.cells[r][c].value != 0 { status = format!("({}, {}) is already filled", r+1, c+1); }
+                        else {
+                            user_elims[r][c] ^= 1 << v;
+                            let struck = user_elims[r][c] & (1 << v) != 0;
+                            status = if struck { format!("Struck {} from ({}, {}); logical steps will skip it", v, r+1, c+1) } else { format!("Restored {} at ({}, {})", v, r+1, c+1) };
+                        }
+                    },
+                    KeyCode::Char(ch @ '1'..='9') if !board.cells[sel.0][sel.1].fixed => {
+                        history.record(board);
+                        board.cells[sel.0][sel.1].value = ch.to_digit(10).unwrap() as u8;
+                        // Start timer on first manual move if not started; playing on ends a pause
+                        timer.start_if_idle();
+                        if timer.is_paused() { timer.toggle_pause(); }
+                        // If solved manually (no brute), record highscore
+                        if board.is_solved() && !used_bruteforce {
+                            let dur_ms = timer.elapsed().as_millis();
+                            let mut hs = highscores::load("highscores.json");
+                            highscores::insert_dedup(&mut hs, highscores::HighscoreEntry {
+                                time_ms: dur_ms,
+                                seed: None,
+                                clues: Some(puzzle_clues),
+                                date_utc: chrono::Utc::now().to_rfc3339(),
+                                solution_sdk: Some(board_to_sdk(board)),
+                            });
+                            let _ = highscores::save("highscores.json", &hs);
+                            hs_list = hs;
+                            status = format!("Solved manually in {}s — saved to highscores", dur_ms / 1000);
+                        }
+                    },
+                    KeyCode::Char('s') => { let _ = fs::write("board.sdk", board_to_sdk(board)); status = "Saved to board.sdk".into(); },
+                    KeyCode::Char('S') if !input_str.is_empty() => {
+                        match fs::write(input_str.trim(), board_to_sdk(board)) { Ok(_) => status = format!("Saved {}", input_str.trim()), Err(e) => status = format!("Save failed: {}", e) }
+                    },
+                    KeyCode::Backspace if !board.cells[sel.0][sel.1].fixed => { history.record(board); board.cells[sel.0][sel.1].value=0; },
+                    _ => {}
+                }
             }
         }
     }
 }
 
+// Indices of the entries to list: all of them, or the given clue count's best times (entries without a
+// clue count only appear unfiltered)
+fn shown_highscores(list: &[highscores::HighscoreEntry], filter: Option<usize>) -> Vec<usize> {
+    match filter {
+        None => (0..list.len()).collect(),
+        Some(n) => highscores::top_n_by_clues(list, n, list.len()).into_iter()
+            .filter_map(|e| list.iter().position(|x| std::ptr::eq(x, e))).collect(),
+    }
+}
+
+// The user's struck candidates as marks for `LogicalSolver::solve_steps_with_marks`; an untouched cell is 0
+fn solver_marks(elims: &[[u16; 9]; 9]) -> [[u16; 9]; 9] {
+    elims.map(|row| row.map(|e| if e == 0 { 0 } else { 0x3FE & !e }))
+}
+
+fn describe_step(kind: &StepKind) -> String {
+    match kind {
+        StepKind::Place{ r,c,v,reason, .. } => format!("Place {} at ({}, {}) — {}", v, r+1, c+1, reason),
+        StepKind::Eliminate{ r,c,v,reason } => format!("Eliminate {} from ({}, {}) — {}", v, r+1, c+1, reason),
+        StepKind::Guess{ r,c,v } => format!("Guess {} at ({}, {})", v, r+1, c+1),
+        StepKind::Backtrack => "Backtrack".to_string(),
+    }
+}
+
 fn board_to_sdk(b: &Board) -> String {
     let mut s = String::with_capacity(81);
     for r in 0..9 { for c in 0..9 { let v=b.cells[r][c].value; s.push(if v==0 { '.' } else { char::from(b'0'+v) }); }}
     s
 }
 
-fn super_simplify_normalize(raw: &str) -> Result<String, ()> {
-    let mut out = String::with_capacity(81);
-    for ch in raw.chars() {
-        match ch { '1'..='9' => out.push(ch), '0'|'.' => out.push('.'), _=>{} }
-        if out.len()==81 { break; }
-    }
-    if out.len()==81 { Ok(out) } else { Err(()) }
-}